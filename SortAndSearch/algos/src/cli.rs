@@ -0,0 +1,558 @@
+//! Command-line arguments shared by the SortAndSearch binaries.
+//!
+//! Each binary describes the flags it accepts with a [`Cli`]. Required values
+//! that are missing from the command line are prompted for when stdin is a
+//! terminal; otherwise the binary prints its usage and exits with status 2.
+
+use std::fmt;
+use std::io::{self, IsTerminal};
+use std::ops::RangeInclusive;
+use std::process;
+
+use crate::distribution::Distribution;
+use crate::prng::{self, Prng, SeedError};
+use crate::sort::external::Format;
+use crate::sort::Algorithm;
+use crate::util::{get_distribution, get_i32};
+
+/// The exit status for bad command-line arguments or input.
+pub const USAGE_ERROR: i32 = 2;
+
+/// A command-line flag understood by the binaries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flag {
+    Items,
+    Max,
+    Seed,
+    Print,
+    Target,
+    Dist,
+    Input,
+    Output,
+    Column,
+    Algo,
+    Memory,
+    Format,
+}
+
+impl Flag {
+    const ALL: [Flag; 12] = [
+        Flag::Items,
+        Flag::Max,
+        Flag::Seed,
+        Flag::Print,
+        Flag::Target,
+        Flag::Dist,
+        Flag::Input,
+        Flag::Output,
+        Flag::Column,
+        Flag::Algo,
+        Flag::Memory,
+        Flag::Format,
+    ];
+
+    /// The flag as written on the command line, such as `--items`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Flag::Items => "--items",
+            Flag::Max => "--max",
+            Flag::Seed => "--seed",
+            Flag::Print => "--print",
+            Flag::Target => "--target",
+            Flag::Dist => "--dist",
+            Flag::Input => "--input",
+            Flag::Output => "--output",
+            Flag::Column => "--column",
+            Flag::Algo => "--algo",
+            Flag::Memory => "--memory",
+            Flag::Format => "--format",
+        }
+    }
+
+    fn value_name(self) -> &'static str {
+        match self {
+            Flag::Items | Flag::Print => "N",
+            Flag::Max => "MAX",
+            Flag::Seed => "SEED",
+            Flag::Target => "T",
+            Flag::Dist => "SHAPE",
+            Flag::Input | Flag::Output => "PATH",
+            Flag::Column => "N",
+            Flag::Algo => "NAME",
+            Flag::Memory => "BYTES",
+            Flag::Format => "FORMAT",
+        }
+    }
+
+    fn help(self) -> &'static str {
+        match self {
+            Flag::Items => "number of items to generate; must be positive",
+            Flag::Max => "generate values in [0, MAX); must be positive",
+            Flag::Seed => "seed for the random generator (default: PRNG_SEED or the time)",
+            Flag::Print => "number of items to print",
+            Flag::Target => "value to search for",
+            Flag::Dist => "data distribution, such as sorted or zipf:1.5 (default: uniform)",
+            Flag::Input => "file to read, or - for stdin (default: stdin)",
+            Flag::Output => "file to write, or - for stdout (default: stdout)",
+            Flag::Column => "sort CSV rows by this column, counting from 1",
+            Flag::Algo => "sorting algorithm, such as heap or natural-merge (default: quick)",
+            Flag::Memory => "sort externally within this budget, such as 64M",
+            Flag::Format => "text or binary little-endian i32s (default: text)",
+        }
+    }
+
+    // The values an integer flag accepts.
+    fn range(self) -> RangeInclusive<i32> {
+        match self {
+            Flag::Items | Flag::Max => 1..=i32::MAX,
+            _ => i32::MIN..=i32::MAX,
+        }
+    }
+
+    // Parse the value of an integer flag, checking its range.
+    fn parse_i32(self, value: &str) -> Result<i32, ArgError> {
+        match value.trim().parse::<i32>() {
+            Ok(n) if self.range().contains(&n) => Ok(n),
+            _ => Err(ArgError::InvalidValue {
+                flag: self,
+                value: value.to_string(),
+            }),
+        }
+    }
+}
+
+/// The values given on the command line. Flags that were not given are
+/// `None`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Args {
+    pub items: Option<i32>,
+    pub max: Option<i32>,
+    pub seed: Option<u64>,
+    pub print: Option<usize>,
+    pub target: Option<i32>,
+    pub dist: Option<Distribution>,
+    pub input: Option<String>,
+    pub output: Option<String>,
+    pub column: Option<usize>,
+    pub algo: Option<Algorithm>,
+    pub memory: Option<usize>,
+    pub format: Option<Format>,
+}
+
+impl Args {
+    /// Return true if no flags were given.
+    pub fn is_empty(&self) -> bool {
+        *self == Args::default()
+    }
+
+    /// Create a generator from `--seed`, or with [`Prng::new`] if it was not
+    /// given.
+    pub fn prng(&self) -> Prng {
+        match self.seed {
+            Some(seed) => Prng::with_seed(seed),
+            None => Prng::new(),
+        }
+    }
+}
+
+/// The ways the command line can be wrong.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArgError {
+    /// `--help` or `-h` was given.
+    Help,
+    /// The argument is not a flag this binary accepts.
+    UnknownFlag(String),
+    /// The flag was the last argument, with no value after it.
+    MissingValue(Flag),
+    /// The flag's value could not be parsed or is out of range.
+    InvalidValue { flag: Flag, value: String },
+    /// The flag was given more than once.
+    Repeated(Flag),
+    /// A required flag was not given and could not be prompted for.
+    Missing(Flag),
+    /// The two flags cannot be used together.
+    Conflict(Flag, Flag),
+    /// `PRNG_SEED` is set but is not a valid seed.
+    Seed(SeedError),
+}
+
+impl fmt::Display for ArgError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ArgError::Help => write!(f, "help requested"),
+            ArgError::UnknownFlag(arg) => write!(f, "unexpected argument '{arg}'"),
+            ArgError::MissingValue(flag) => write!(f, "{} needs a value", flag.name()),
+            ArgError::InvalidValue { flag, value } => {
+                write!(f, "invalid value '{value}' for {}", flag.name())
+            }
+            ArgError::Repeated(flag) => write!(f, "{} given more than once", flag.name()),
+            ArgError::Missing(flag) => write!(f, "missing required {}", flag.name()),
+            ArgError::Conflict(a, b) => {
+                write!(f, "{} cannot be used with {}", a.name(), b.name())
+            }
+            ArgError::Seed(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for ArgError {}
+
+/// The name and flags of one binary.
+#[derive(Debug, Clone, Copy)]
+pub struct Cli {
+    pub name: &'static str,
+    pub about: &'static str,
+    pub flags: &'static [Flag],
+}
+
+impl Cli {
+    /// Parse the arguments, not including the program name. Values may be
+    /// given as `--items 10` or `--items=10`.
+    pub fn parse<I, S>(&self, args: I) -> Result<Args, ArgError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut parsed = Args::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let arg = arg.as_ref();
+            if arg == "--help" || arg == "-h" {
+                return Err(ArgError::Help);
+            }
+            let (name, inline_value) = match arg.split_once('=') {
+                Some((name, value)) => (name, Some(value.to_string())),
+                None => (arg, None),
+            };
+            let flag = self
+                .flags
+                .iter()
+                .copied()
+                .find(|flag| flag.name() == name)
+                .ok_or_else(|| ArgError::UnknownFlag(arg.to_string()))?;
+            let value = match inline_value {
+                Some(value) => value,
+                None => match args.next() {
+                    Some(value) => value.as_ref().to_string(),
+                    None => return Err(ArgError::MissingValue(flag)),
+                },
+            };
+            set(&mut parsed, flag, &value)?;
+        }
+        Ok(parsed)
+    }
+
+    /// Parse the process arguments and check `PRNG_SEED`. Print the usage
+    /// and exit on `--help` or on an error.
+    pub fn parse_env(&self) -> Args {
+        if let Err(err) = prng::env_seed() {
+            self.fail(ArgError::Seed(err));
+        }
+        match self.parse(std::env::args().skip(1)) {
+            Ok(args) => args,
+            Err(ArgError::Help) => {
+                print!("{}", self.usage());
+                process::exit(0);
+            }
+            Err(err) => self.fail(err),
+        }
+    }
+
+    /// Return the usage message.
+    pub fn usage(&self) -> String {
+        let mut usage = format!(
+            "{}\n\nUsage: {} [OPTIONS]\n\nOptions:\n",
+            self.about, self.name
+        );
+        for flag in Flag::ALL.iter().filter(|flag| self.flags.contains(flag)) {
+            let spec = format!("{} <{}>", flag.name(), flag.value_name());
+            usage.push_str(&format!("  {spec:<18} {}\n", flag.help()));
+        }
+        usage.push_str(&format!("  {:<18} print this message\n", "-h, --help"));
+        usage
+    }
+
+    /// Print the error and the usage, then exit with [`USAGE_ERROR`].
+    pub fn fail(&self, err: ArgError) -> ! {
+        eprintln!("{}: {err}\n", self.name);
+        eprint!("{}", self.usage());
+        process::exit(USAGE_ERROR);
+    }
+
+    /// Return the value of a required integer flag. If it was not given,
+    /// prompt for it with [`ask`](Self::ask), and exit cleanly at end of
+    /// file.
+    pub fn require(&self, value: Option<i32>, flag: Flag, prompt: &str) -> i32 {
+        value
+            .or_else(|| self.ask(flag, prompt))
+            .unwrap_or_else(|| process::exit(0))
+    }
+
+    /// Fail if the flag's value is missing and cannot be prompted for
+    /// because stdin is not a terminal. Call this before doing any work for
+    /// a flag that is only prompted for later.
+    pub fn check_prompt(&self, value: Option<i32>, flag: Flag) {
+        if value.is_none() && !io::stdin().is_terminal() {
+            self.fail(ArgError::Missing(flag));
+        }
+    }
+
+    /// Prompt for the value of an integer flag until the user enters a valid
+    /// one, or return `None` at end of file. Fail if stdin is not a
+    /// terminal.
+    pub fn ask(&self, flag: Flag, prompt: &str) -> Option<i32> {
+        self.check_prompt(None, flag);
+        match get_i32(prompt, flag.range()) {
+            Ok(value) => value,
+            Err(err) => {
+                eprintln!("{}: {err}", self.name);
+                process::exit(1);
+            }
+        }
+    }
+
+    /// Return the `--dist` value. With no flags at all on a terminal, prompt
+    /// for it until the user enters a valid one, and exit cleanly at end of
+    /// file; otherwise default to [`Distribution::Uniform`].
+    pub fn dist_or_prompt(&self, args: &Args, prompt: &str) -> Distribution {
+        if let Some(dist) = args.dist {
+            return dist;
+        }
+        if !(args.is_empty() && io::stdin().is_terminal()) {
+            return Distribution::default();
+        }
+        match get_distribution(prompt) {
+            Ok(Some(dist)) => dist,
+            Ok(None) => process::exit(0),
+            Err(err) => {
+                eprintln!("{}: {err}", self.name);
+                process::exit(1);
+            }
+        }
+    }
+}
+
+// Store the value of one flag.
+fn set(args: &mut Args, flag: Flag, value: &str) -> Result<(), ArgError> {
+    let invalid = || ArgError::InvalidValue {
+        flag,
+        value: value.to_string(),
+    };
+    let repeated = match flag {
+        Flag::Items => args.items.replace(flag.parse_i32(value)?).is_some(),
+        Flag::Max => args.max.replace(flag.parse_i32(value)?).is_some(),
+        Flag::Target => args.target.replace(flag.parse_i32(value)?).is_some(),
+        Flag::Seed => {
+            let seed = value.trim().parse().map_err(|_| invalid())?;
+            args.seed.replace(seed).is_some()
+        }
+        Flag::Print => {
+            let print = value.trim().parse().map_err(|_| invalid())?;
+            args.print.replace(print).is_some()
+        }
+        Flag::Dist => {
+            let dist = value.parse().map_err(|_| invalid())?;
+            args.dist.replace(dist).is_some()
+        }
+        Flag::Input => args.input.replace(value.to_string()).is_some(),
+        Flag::Output => args.output.replace(value.to_string()).is_some(),
+        Flag::Column => {
+            let column = value.trim().parse().ok().filter(|&c| c > 0);
+            args.column.replace(column.ok_or_else(invalid)?).is_some()
+        }
+        Flag::Algo => {
+            let algo = value.parse().map_err(|_| invalid())?;
+            args.algo.replace(algo).is_some()
+        }
+        Flag::Memory => {
+            let memory = parse_bytes(value).ok_or_else(invalid)?;
+            args.memory.replace(memory).is_some()
+        }
+        Flag::Format => {
+            let format = value.parse().map_err(|_| invalid())?;
+            args.format.replace(format).is_some()
+        }
+    };
+    if repeated {
+        return Err(ArgError::Repeated(flag));
+    }
+    Ok(())
+}
+
+// Parse a positive byte count with an optional K, M or G suffix.
+fn parse_bytes(value: &str) -> Option<usize> {
+    let value = value.trim();
+    let (digits, shift) = match value.char_indices().last()? {
+        (i, 'k' | 'K') => (&value[..i], 10),
+        (i, 'm' | 'M') => (&value[..i], 20),
+        (i, 'g' | 'G') => (&value[..i], 30),
+        _ => (value, 0),
+    };
+    let n: usize = digits.parse().ok().filter(|&n| n > 0)?;
+    n.checked_mul(1 << shift)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CLI: Cli = Cli {
+        name: "test",
+        about: "Test the parser.",
+        flags: &[Flag::Items, Flag::Max, Flag::Seed, Flag::Print, Flag::Dist],
+    };
+
+    #[test]
+    fn test_parse_all_flags() {
+        let args = CLI
+            .parse([
+                "--items",
+                "20",
+                "--max=100",
+                "--seed",
+                "42",
+                "--print",
+                "5",
+                "--dist",
+                "reverse",
+            ])
+            .unwrap();
+        assert_eq!(
+            args,
+            Args {
+                items: Some(20),
+                max: Some(100),
+                seed: Some(42),
+                print: Some(5),
+                dist: Some(Distribution::Reverse),
+                ..Default::default()
+            }
+        );
+        assert!(!args.is_empty());
+        assert_eq!(args.prng().initial_seed(), 42);
+    }
+
+    #[test]
+    fn test_parse_file_flags() {
+        let cli = Cli {
+            flags: &[Flag::Input, Flag::Output, Flag::Column, Flag::Algo],
+            ..CLI
+        };
+        let args = cli
+            .parse([
+                "--input",
+                "in.csv",
+                "--output=-",
+                "--column",
+                "2",
+                "--algo",
+                "radix",
+            ])
+            .unwrap();
+        assert_eq!(args.input.as_deref(), Some("in.csv"));
+        assert_eq!(args.output.as_deref(), Some("-"));
+        assert_eq!(args.column, Some(2));
+        assert_eq!(args.algo, Some(Algorithm::Radix));
+
+        for (flag, value) in [(Flag::Column, "0"), (Flag::Algo, "bogo")] {
+            assert_eq!(
+                cli.parse([flag.name(), value]),
+                Err(ArgError::InvalidValue {
+                    flag,
+                    value: value.to_string()
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_bytes() {
+        assert_eq!(parse_bytes("4096"), Some(4096));
+        assert_eq!(parse_bytes("64k"), Some(64 << 10));
+        assert_eq!(parse_bytes("2M"), Some(2 << 20));
+        assert_eq!(parse_bytes("1G"), Some(1 << 30));
+        for bad in ["", "0", "M", "-5", "1.5M", "12T"] {
+            assert_eq!(parse_bytes(bad), None, "{bad:?}");
+        }
+    }
+
+    #[test]
+    fn test_parse_nothing() {
+        let args = CLI.parse(Vec::<String>::new()).unwrap();
+        assert!(args.is_empty());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(CLI.parse(["-h"]), Err(ArgError::Help));
+        assert_eq!(CLI.parse(["--items", "1", "--help"]), Err(ArgError::Help));
+        assert_eq!(
+            CLI.parse(["--target", "3"]),
+            Err(ArgError::UnknownFlag("--target".to_string()))
+        );
+        assert_eq!(
+            CLI.parse(["10"]),
+            Err(ArgError::UnknownFlag("10".to_string()))
+        );
+        assert_eq!(
+            CLI.parse(["--items"]),
+            Err(ArgError::MissingValue(Flag::Items))
+        );
+        assert_eq!(
+            CLI.parse(["--items", "1", "--items", "2"]),
+            Err(ArgError::Repeated(Flag::Items))
+        );
+        for (flag, value) in [
+            (Flag::Items, "ten"),
+            (Flag::Items, "0"),
+            (Flag::Max, "0"),
+            (Flag::Seed, "-5"),
+            (Flag::Print, "x"),
+            (Flag::Dist, "spiral"),
+        ] {
+            assert_eq!(
+                CLI.parse([flag.name(), value]),
+                Err(ArgError::InvalidValue {
+                    flag,
+                    value: value.to_string()
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn test_usage_lists_accepted_flags() {
+        let usage = CLI.usage();
+        assert!(usage.starts_with("Test the parser."));
+        assert!(usage.contains("--items <N>"));
+        assert!(usage.contains("--dist <SHAPE>"));
+        assert!(usage.contains("--help"));
+        assert!(!usage.contains("--target"));
+    }
+
+    #[test]
+    fn test_error_messages() {
+        assert_eq!(
+            ArgError::InvalidValue {
+                flag: Flag::Max,
+                value: "0".to_string()
+            }
+            .to_string(),
+            "invalid value '0' for --max"
+        );
+        assert_eq!(
+            ArgError::Missing(Flag::Target).to_string(),
+            "missing required --target"
+        );
+        assert_eq!(
+            ArgError::Seed(SeedError {
+                value: "x".to_string()
+            })
+            .to_string(),
+            format!(
+                "invalid PRNG_SEED 'x': must be an integer in [0, {}]",
+                u64::MAX
+            )
+        );
+    }
+}
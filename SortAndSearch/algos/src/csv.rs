@@ -0,0 +1,325 @@
+//! Minimal reading and writing of comma-separated values.
+//!
+//! Each record is one line, unless a quoted field holds a line break. Fields
+//! may be wrapped in double quotes to hold commas and line breaks, and a
+//! doubled quote inside a quoted field stands for one quote.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::io::{self, BufRead};
+
+/// The ways a CSV record can be malformed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CsvError {
+    /// A quoted field was not closed before the end of the line.
+    UnterminatedQuote { field: usize },
+    /// A closing quote was followed by something other than a comma.
+    TextAfterQuote { field: usize },
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CsvError::UnterminatedQuote { field } => {
+                write!(f, "field {field} has an unterminated quote")
+            }
+            CsvError::TextAfterQuote { field } => {
+                write!(f, "field {field} has text after its closing quote")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CsvError {}
+
+/// Split one record into its fields, removing quotes. A trailing carriage
+/// return is ignored. Fields are numbered from 1 in errors.
+pub fn parse_record(line: &str) -> Result<Vec<String>, CsvError> {
+    let line = line.strip_suffix('\r').unwrap_or(line);
+    let mut fields = Vec::new();
+    let mut chars = line.chars().peekable();
+    loop {
+        let number = fields.len() + 1;
+        let mut field = String::new();
+        if chars.peek() == Some(&'"') {
+            chars.next();
+            loop {
+                match chars.next() {
+                    Some('"') if chars.peek() == Some(&'"') => {
+                        chars.next();
+                        field.push('"');
+                    }
+                    Some('"') => break,
+                    Some(c) => field.push(c),
+                    None => return Err(CsvError::UnterminatedQuote { field: number }),
+                }
+            }
+            match chars.next() {
+                Some(',') => fields.push(field),
+                None => {
+                    fields.push(field);
+                    return Ok(fields);
+                }
+                Some(_) => return Err(CsvError::TextAfterQuote { field: number }),
+            }
+        } else {
+            loop {
+                match chars.next() {
+                    Some(',') => break,
+                    Some(c) => field.push(c),
+                    None => {
+                        fields.push(field);
+                        return Ok(fields);
+                    }
+                }
+            }
+            fields.push(field);
+        }
+    }
+}
+
+/// Join the fields into one record, quoting those that contain a comma, a
+/// quote or a line break. [`Records`] reads such a record back whole.
+pub fn format_record<S: AsRef<str>>(fields: &[S]) -> String {
+    let quoted: Vec<String> = fields.iter().map(|f| quote_field(f.as_ref())).collect();
+    quoted.join(",")
+}
+
+// Quote the field if it would not survive parse_record unchanged.
+fn quote_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// One record read by [`Records`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    /// The line the record starts on, counting from 1.
+    pub line: usize,
+    /// The text of the record, without its final line ending.
+    pub text: String,
+}
+
+/// An iterator over the records of CSV text, joining the lines of quoted
+/// fields that hold line breaks.
+///
+/// A quote that is still open at the end of the input makes its first line a
+/// record of its own, which [`parse_record`] rejects, and reading resumes on
+/// the line after it.
+pub struct Records<R> {
+    input: R,
+    lines_read: usize,
+    // Lines read past an unterminated quote, to be read again.
+    pending: VecDeque<String>,
+}
+
+impl<R: BufRead> Records<R> {
+    /// Read records from `input`.
+    pub fn new(input: R) -> Self {
+        Self {
+            input,
+            lines_read: 0,
+            pending: VecDeque::new(),
+        }
+    }
+
+    // Read the next line, including its line ending, or None at the end of
+    // the input.
+    fn next_line(&mut self) -> io::Result<Option<String>> {
+        if let Some(line) = self.pending.pop_front() {
+            return Ok(Some(line));
+        }
+        let mut line = String::new();
+        match self.input.read_line(&mut line)? {
+            0 => Ok(None),
+            _ => Ok(Some(line)),
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for Records<R> {
+    type Item = io::Result<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let line = self.lines_read + 1;
+        let mut lines: Vec<String> = Vec::new();
+        let mut quotes = QuoteState::default();
+        loop {
+            match self.next_line() {
+                Ok(Some(next)) => {
+                    let open = quotes.scan(&next);
+                    lines.push(next);
+                    if !open {
+                        break;
+                    }
+                }
+                Ok(None) if lines.is_empty() => return None,
+                Ok(None) => {
+                    // Give back every line after the one that opened the quote.
+                    for rest in lines.drain(1..).rev() {
+                        self.pending.push_front(rest);
+                    }
+                    break;
+                }
+                Err(err) => return Some(Err(err)),
+            }
+        }
+        self.lines_read += lines.len();
+        let text = lines.concat();
+        let text = text.strip_suffix('\n').unwrap_or(&text);
+        let text = text.strip_suffix('\r').unwrap_or(text);
+        Some(Ok(Record {
+            line,
+            text: text.to_string(),
+        }))
+    }
+}
+
+// Tracks whether a record read so far ends inside a quoted field, following
+// the rules of parse_record.
+#[derive(Debug)]
+struct QuoteState {
+    in_quotes: bool,
+    field_start: bool,
+}
+
+impl Default for QuoteState {
+    fn default() -> Self {
+        Self {
+            in_quotes: false,
+            field_start: true,
+        }
+    }
+}
+
+impl QuoteState {
+    // Scan the next line of the record and return true if it ends inside
+    // a quoted field.
+    fn scan(&mut self, line: &str) -> bool {
+        let mut chars = line.chars().peekable();
+        while let Some(c) = chars.next() {
+            if self.in_quotes {
+                if c == '"' {
+                    if chars.peek() == Some(&'"') {
+                        chars.next();
+                    } else {
+                        self.in_quotes = false;
+                    }
+                }
+            } else if self.field_start && c == '"' {
+                self.in_quotes = true;
+                self.field_start = false;
+            } else {
+                self.field_start = c == ',';
+            }
+        }
+        self.in_quotes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_plain() {
+        assert_eq!(parse_record("a,b,,c").unwrap(), ["a", "b", "", "c"]);
+        assert_eq!(parse_record("").unwrap(), [""]);
+        assert_eq!(parse_record("x,").unwrap(), ["x", ""]);
+        assert_eq!(parse_record("1,2\r").unwrap(), ["1", "2"]);
+    }
+
+    #[test]
+    fn test_parse_quoted() {
+        assert_eq!(
+            parse_record(r##""Smith, J.",42,"say ""hi""","""##).unwrap(),
+            ["Smith, J.", "42", r#"say "hi""#, ""]
+        );
+        // Quotes are only special at the start of a field.
+        assert_eq!(parse_record(r#"5" tall,x"#).unwrap(), [r#"5" tall"#, "x"]);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            parse_record(r#"a,"open"#),
+            Err(CsvError::UnterminatedQuote { field: 2 })
+        );
+        assert_eq!(
+            parse_record(r#""a"b,c"#),
+            Err(CsvError::TextAfterQuote { field: 1 })
+        );
+    }
+
+    #[test]
+    fn test_format_round_trip() {
+        let fields = ["plain", "with,comma", r#"with "quotes""#, ""];
+        let line = format_record(&fields);
+        assert_eq!(line, r#"plain,"with,comma","with ""quotes""","#);
+        assert_eq!(parse_record(&line).unwrap(), fields);
+    }
+
+    // Read every record of the text, returning its line and fields.
+    fn read_records(text: &str) -> Vec<(usize, Vec<String>)> {
+        Records::new(text.as_bytes())
+            .map(|record| {
+                let record = record.unwrap();
+                (record.line, parse_record(&record.text).unwrap())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_records_join_quoted_line_breaks() {
+        let fields = ["two\nlines", "crlf\r\nend", r#"quote ""#, "x"];
+        let text = format!("{}\r\nlast,\"\"\n", format_record(&fields));
+        assert_eq!(
+            read_records(&text),
+            [
+                (1, fields.map(String::from).to_vec()),
+                (4, vec!["last".to_string(), String::new()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_records_plain_lines() {
+        let records = read_records("a,b\n\n5\" tall,c\r\nd");
+        assert_eq!(records.len(), 4);
+        assert_eq!(records[1], (2, vec![String::new()]));
+        assert_eq!(
+            records[2],
+            (3, vec![r#"5" tall"#.to_string(), "c".to_string()])
+        );
+        assert_eq!(records[3], (4, vec!["d".to_string()]));
+    }
+
+    #[test]
+    fn test_records_unterminated_quote() {
+        let mut records = Records::new("a,\"open\nb,c\nd\n".as_bytes());
+        let record = records.next().unwrap().unwrap();
+        assert_eq!(record.line, 1);
+        assert_eq!(
+            parse_record(&record.text),
+            Err(CsvError::UnterminatedQuote { field: 2 })
+        );
+        // Reading resumes after the line that opened the quote.
+        let rest: Vec<Record> = records.map(Result::unwrap).collect();
+        assert_eq!(
+            rest,
+            [
+                Record {
+                    line: 2,
+                    text: "b,c".to_string()
+                },
+                Record {
+                    line: 3,
+                    text: "d".to_string()
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,412 @@
+//! Shapes of test data for the sorting benchmarks and binaries.
+//!
+//! Every [`Distribution`] produces values in the range [0, max), drawn from
+//! any [`RandomSource`], so a seeded [`Prng`] replays the same input.
+
+use std::f64::consts::TAU;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::prng::{Prng, RandomSource, Sampling};
+
+/// The shape of a generated vector.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Distribution {
+    /// Independent uniform values.
+    #[default]
+    Uniform,
+    /// Uniform values in ascending order.
+    Sorted,
+    /// Uniform values in descending order.
+    Reverse,
+    /// Sorted values with `swaps` random pairs exchanged.
+    NearlySorted { swaps: usize },
+    /// Ascending values up to the middle, then descending ones.
+    OrganPipe,
+    /// `teeth` consecutive ascending runs.
+    Sawtooth { teeth: usize },
+    /// Values picked from only `distinct` different keys.
+    FewUnique { distinct: usize },
+    /// Zipf-distributed values: value k - 1 has weight 1 / k^exponent, so
+    /// small values dominate.
+    Zipf { exponent: f64 },
+    /// Normally distributed values with mean max / 2 and standard deviation
+    /// max / 6, clamped to the range.
+    Gaussian,
+}
+
+impl Distribution {
+    /// One of every shape, with default parameters.
+    pub const ALL: [Distribution; 9] = [
+        Distribution::Uniform,
+        Distribution::Sorted,
+        Distribution::Reverse,
+        Distribution::NearlySorted { swaps: 10 },
+        Distribution::OrganPipe,
+        Distribution::Sawtooth { teeth: 8 },
+        Distribution::FewUnique { distinct: 10 },
+        Distribution::Zipf { exponent: 1.0 },
+        Distribution::Gaussian,
+    ];
+
+    /// Make a vector of `num_items` values in the range [0, max) with this
+    /// shape. If `max` is not positive, every value is zero.
+    pub fn generate<R>(self, prng: &mut R, num_items: i32, max: i32) -> Vec<i32>
+    where
+        R: RandomSource + ?Sized,
+    {
+        let n = num_items.max(0) as usize;
+        let max = max.max(1);
+        match self {
+            Distribution::Uniform => uniform(prng, n, max),
+            Distribution::Sorted => sorted(prng, n, max),
+            Distribution::Reverse => {
+                let mut vec = sorted(prng, n, max);
+                vec.reverse();
+                vec
+            }
+            Distribution::NearlySorted { swaps } => {
+                let mut vec = sorted(prng, n, max);
+                if n > 1 {
+                    for _ in 0..swaps {
+                        let i = prng.index(n);
+                        let j = prng.index(n);
+                        vec.swap(i, j);
+                    }
+                }
+                vec
+            }
+            Distribution::OrganPipe => {
+                let mut vec = sorted(prng, n, max);
+                // Take every other value going up, and the rest coming down.
+                let descending: Vec<i32> = vec.iter().skip(1).step_by(2).rev().copied().collect();
+                let ascending: Vec<i32> = vec.iter().step_by(2).copied().collect();
+                vec.clear();
+                vec.extend(ascending);
+                vec.extend(descending);
+                vec
+            }
+            Distribution::Sawtooth { teeth } => {
+                let mut vec = uniform(prng, n, max);
+                let tooth_len = n.div_ceil(teeth.max(1)).max(1);
+                for tooth in vec.chunks_mut(tooth_len) {
+                    tooth.sort_unstable();
+                }
+                vec
+            }
+            Distribution::FewUnique { distinct } => {
+                let keys = uniform(prng, distinct.max(1), max);
+                (0..n).map(|_| *prng.choose(&keys).unwrap()).collect()
+            }
+            Distribution::Zipf { exponent } => {
+                let zipf = Zipf::new(max as u32, exponent);
+                (0..n).map(|_| zipf.sample(prng) as i32 - 1).collect()
+            }
+            Distribution::Gaussian => {
+                let mean = max as f64 / 2.0;
+                let std_dev = max as f64 / 6.0;
+                (0..n)
+                    .map(|_| {
+                        let x = mean + std_dev * standard_normal(prng);
+                        x.clamp(0.0, (max - 1) as f64) as i32
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+impl fmt::Display for Distribution {
+    /// Write the name accepted by [`FromStr`], such as `nearly-sorted:10`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Distribution::Uniform => write!(f, "uniform"),
+            Distribution::Sorted => write!(f, "sorted"),
+            Distribution::Reverse => write!(f, "reverse"),
+            Distribution::NearlySorted { swaps } => write!(f, "nearly-sorted:{swaps}"),
+            Distribution::OrganPipe => write!(f, "organ-pipe"),
+            Distribution::Sawtooth { teeth } => write!(f, "sawtooth:{teeth}"),
+            Distribution::FewUnique { distinct } => write!(f, "few-unique:{distinct}"),
+            Distribution::Zipf { exponent } => write!(f, "zipf:{exponent}"),
+            Distribution::Gaussian => write!(f, "gaussian"),
+        }
+    }
+}
+
+/// The error returned when a distribution name cannot be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseDistributionError(String);
+
+impl fmt::Display for ParseDistributionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown distribution '{}'; expected one of uniform, sorted, reverse, \
+             nearly-sorted[:swaps], organ-pipe, sawtooth[:teeth], few-unique[:distinct], \
+             zipf[:exponent] or gaussian",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for ParseDistributionError {}
+
+impl FromStr for Distribution {
+    type Err = ParseDistributionError;
+
+    /// Parse a name such as `sorted` or `zipf:1.5`. Parameters are optional
+    /// and default to the values in [`Distribution::ALL`].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseDistributionError(s.to_string());
+        let (name, param) = match s.trim().split_once(':') {
+            Some((name, param)) => (name, Some(param.trim())),
+            None => (s.trim(), None),
+        };
+        // Parse the parameter, or use the default if there is none.
+        fn param_or<T: FromStr>(param: Option<&str>, default: T) -> Option<T> {
+            match param {
+                Some(p) => p.parse().ok(),
+                None => Some(default),
+            }
+        }
+        let dist = match name.to_ascii_lowercase().as_str() {
+            "uniform" if param.is_none() => Distribution::Uniform,
+            "sorted" if param.is_none() => Distribution::Sorted,
+            "reverse" if param.is_none() => Distribution::Reverse,
+            "organ-pipe" if param.is_none() => Distribution::OrganPipe,
+            "gaussian" if param.is_none() => Distribution::Gaussian,
+            "nearly-sorted" => Distribution::NearlySorted {
+                swaps: param_or(param, 10).ok_or_else(err)?,
+            },
+            "sawtooth" => Distribution::Sawtooth {
+                teeth: param_or(param, 8).ok_or_else(err)?,
+            },
+            "few-unique" => Distribution::FewUnique {
+                distinct: param_or(param, 10).ok_or_else(err)?,
+            },
+            "zipf" => Distribution::Zipf {
+                exponent: param_or(param, 1.0)
+                    .filter(|e: &f64| e.is_finite() && *e > 0.0)
+                    .ok_or_else(err)?,
+            },
+            _ => return Err(err()),
+        };
+        Ok(dist)
+    }
+}
+
+/// Make a vector of `num_items` values in the range [0, max) with the given
+/// shape, using a fresh [`Prng::new`].
+pub fn make_distributed_vec(dist: Distribution, num_items: i32, max: i32) -> Vec<i32> {
+    dist.generate(&mut Prng::new(), num_items, max)
+}
+
+fn uniform<R: RandomSource + ?Sized>(prng: &mut R, n: usize, max: i32) -> Vec<i32> {
+    (0..n).map(|_| prng.next_i32(0, max)).collect()
+}
+
+fn sorted<R: RandomSource + ?Sized>(prng: &mut R, n: usize, max: i32) -> Vec<i32> {
+    let mut vec = uniform(prng, n, max);
+    vec.sort_unstable();
+    vec
+}
+
+// Draw a standard normal value with the Box-Muller transform.
+fn standard_normal<R: RandomSource + ?Sized>(prng: &mut R) -> f64 {
+    // 1 - u lies in (0, 1], so the logarithm is finite.
+    let u1 = 1.0 - prng.next_f64();
+    let u2 = prng.next_f64();
+    (-2.0 * u1.ln()).sqrt() * (TAU * u2).cos()
+}
+
+// ************
+// *** Zipf ***
+// ************
+// Samples ranks in [1, n] with weight 1 / k^s using Hörmann and Derflinger's
+// rejection-inversion method, which needs no table of n weights.
+struct Zipf {
+    n: f64,
+    s: f64,
+    h_integral_x1: f64,
+    h_integral_n: f64,
+    threshold: f64,
+}
+
+impl Zipf {
+    fn new(n: u32, s: f64) -> Self {
+        let mut zipf = Zipf {
+            n: n as f64,
+            s,
+            h_integral_x1: 0.0,
+            h_integral_n: 0.0,
+            threshold: 0.0,
+        };
+        zipf.h_integral_x1 = zipf.h_integral(1.5) - 1.0;
+        zipf.h_integral_n = zipf.h_integral(zipf.n + 0.5);
+        zipf.threshold = 2.0 - zipf.h_integral_inv(zipf.h_integral(2.5) - zipf.h(2.0));
+        zipf
+    }
+
+    fn sample<R: RandomSource + ?Sized>(&self, prng: &mut R) -> u32 {
+        loop {
+            let u = self.h_integral_n + prng.next_f64() * (self.h_integral_x1 - self.h_integral_n);
+            let x = self.h_integral_inv(u);
+            let k = (x + 0.5).floor().clamp(1.0, self.n);
+            if k - x <= self.threshold || u >= self.h_integral(k + 0.5) - self.h(k) {
+                return k as u32;
+            }
+        }
+    }
+
+    // The unnormalised density 1 / x^s.
+    fn h(&self, x: f64) -> f64 {
+        (-self.s * x.ln()).exp()
+    }
+
+    // An antiderivative of h.
+    fn h_integral(&self, x: f64) -> f64 {
+        let log_x = x.ln();
+        expm1_over_x((1.0 - self.s) * log_x) * log_x
+    }
+
+    // The inverse of h_integral.
+    fn h_integral_inv(&self, x: f64) -> f64 {
+        let t = (x * (1.0 - self.s)).max(-1.0);
+        (ln1p_over_x(t) * x).exp()
+    }
+}
+
+// (e^x - 1) / x, which tends to 1 as x tends to 0.
+fn expm1_over_x(x: f64) -> f64 {
+    if x.abs() > 1e-8 {
+        x.exp_m1() / x
+    } else {
+        1.0 + x / 2.0 * (1.0 + x / 3.0)
+    }
+}
+
+// ln(1 + x) / x, which tends to 1 as x tends to 0.
+fn ln1p_over_x(x: f64) -> f64 {
+    if x.abs() > 1e-8 {
+        x.ln_1p() / x
+    } else {
+        1.0 - x / 2.0 * (1.0 - 2.0 * x / 3.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::is_sorted;
+
+    #[test]
+    fn test_values_in_range() {
+        let mut prng = Prng::with_seed(1);
+        for dist in Distribution::ALL {
+            for (n, max) in [
+                (0, 10),
+                (1, 10),
+                (1_000, 1),
+                (1_000, 50),
+                (1_000, 1_000_000),
+            ] {
+                let vec = dist.generate(&mut prng, n, max);
+                assert_eq!(vec.len(), n as usize, "{dist}");
+                assert!(vec.iter().all(|&x| (0..max).contains(&x)), "{dist}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_seed_is_reproducible() {
+        for dist in Distribution::ALL {
+            let a = dist.generate(&mut Prng::with_seed(3), 500, 1_000);
+            let b = dist.generate(&mut Prng::with_seed(3), 500, 1_000);
+            assert_eq!(a, b, "{dist}");
+        }
+    }
+
+    #[test]
+    fn test_shapes() {
+        let mut prng = Prng::with_seed(5);
+        let n = 1_000;
+
+        assert!(is_sorted(&Distribution::Sorted.generate(&mut prng, n, 100)));
+
+        let mut reverse = Distribution::Reverse.generate(&mut prng, n, 100);
+        reverse.reverse();
+        assert!(is_sorted(&reverse));
+
+        // Each swap displaces at most two items.
+        let nearly = Distribution::NearlySorted { swaps: 5 }.generate(&mut prng, n, 1_000_000);
+        let mut sorted = nearly.clone();
+        sorted.sort();
+        let displaced = nearly.iter().zip(&sorted).filter(|(a, b)| a != b).count();
+        assert!(displaced <= 10, "{displaced} items displaced");
+
+        let pipe = Distribution::OrganPipe.generate(&mut prng, n, 100);
+        let peak = pipe.iter().enumerate().max_by_key(|&(_, x)| x).unwrap().0;
+        assert!(is_sorted(&pipe[..=peak]));
+        assert!(pipe[peak..].windows(2).all(|w| w[0] >= w[1]));
+
+        let saw = Distribution::Sawtooth { teeth: 4 }.generate(&mut prng, n, 100);
+        assert!(saw.chunks(250).all(is_sorted));
+
+        let mut few = Distribution::FewUnique { distinct: 5 }.generate(&mut prng, n, 1_000_000);
+        few.sort();
+        few.dedup();
+        assert!(few.len() <= 5);
+    }
+
+    #[test]
+    fn test_zipf_frequencies() {
+        // With exponent 1 over 100 ranks, rank 1 has weight 1 / H(100), about
+        // 19%, and rank 2 half of that.
+        let mut prng = Prng::with_seed(7);
+        let n = 100_000;
+        let vec = Distribution::Zipf { exponent: 1.0 }.generate(&mut prng, n, 100);
+        let harmonic: f64 = (1..=100).map(|k| 1.0 / k as f64).sum();
+        for k in 1..=3 {
+            let count = vec.iter().filter(|&&x| x == k - 1).count() as f64;
+            let expected = n as f64 / (k as f64 * harmonic);
+            assert!(
+                (count - expected).abs() < 0.05 * expected,
+                "rank {k}: {count} vs {expected}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_gaussian_moments() {
+        let mut prng = Prng::with_seed(11);
+        let n = 100_000;
+        let vec = Distribution::Gaussian.generate(&mut prng, n, 6_000);
+        let mean = vec.iter().map(|&x| x as f64).sum::<f64>() / n as f64;
+        let var = vec.iter().map(|&x| (x as f64 - mean).powi(2)).sum::<f64>() / n as f64;
+        assert!((mean - 3_000.0).abs() < 20.0, "mean {mean}");
+        assert!(
+            (var.sqrt() - 1_000.0).abs() < 20.0,
+            "std dev {}",
+            var.sqrt()
+        );
+    }
+
+    #[test]
+    fn test_parse_round_trip() {
+        for dist in Distribution::ALL {
+            assert_eq!(dist.to_string().parse::<Distribution>(), Ok(dist));
+        }
+        assert_eq!("Sorted".parse(), Ok(Distribution::Sorted));
+        assert_eq!(
+            "nearly-sorted".parse(),
+            Ok(Distribution::NearlySorted { swaps: 10 })
+        );
+        assert_eq!(
+            "zipf: 1.5".parse(),
+            Ok(Distribution::Zipf { exponent: 1.5 })
+        );
+        for bad in ["", "random", "sorted:3", "sawtooth:x", "zipf:0", "zipf:-1"] {
+            assert!(bad.parse::<Distribution>().is_err(), "{bad:?}");
+        }
+    }
+}
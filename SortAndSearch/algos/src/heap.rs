@@ -0,0 +1,503 @@
+//! Binary heaps.
+//!
+//! The free functions maintain a heap inside a slice, with the greatest item
+//! under `cmp` at index 0 and the children of index `i` at `2i + 1` and
+//! `2i + 2`. They need no memory of their own, which is what
+//! [`heap_sort`](crate::heap_sort) relies on. [`PriorityQueue`] builds a
+//! min-first queue on top of them whose items can be found again through a
+//! [`Handle`] to lower their key or remove them.
+
+use std::cmp::Ordering;
+use std::fmt;
+
+/// Move the item at `pos` up towards the root until its parent is not less
+/// than it. Return the item's new index.
+pub fn sift_up_by<T, F>(vec: &mut [T], mut pos: usize, cmp: &mut F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    while pos > 0 {
+        let parent = (pos - 1) / 2;
+        if cmp(&vec[parent], &vec[pos]) != Ordering::Less {
+            break;
+        }
+        vec.swap(parent, pos);
+        pos = parent;
+    }
+    pos
+}
+
+/// Move the item at `pos` down until neither child is greater than it.
+/// Return the item's new index.
+pub fn sift_down_by<T, F>(vec: &mut [T], mut pos: usize, cmp: &mut F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let end = vec.len();
+    loop {
+        let mut child = 2 * pos + 1;
+        if child >= end {
+            break;
+        }
+        if child + 1 < end && cmp(&vec[child], &vec[child + 1]) == Ordering::Less {
+            child += 1;
+        }
+        if cmp(&vec[pos], &vec[child]) != Ordering::Less {
+            break;
+        }
+        vec.swap(pos, child);
+        pos = child;
+    }
+    pos
+}
+
+/// Rearrange the slice into a max-heap in O(n) time.
+pub fn heapify<T: Ord>(vec: &mut [T]) {
+    heapify_by(vec, T::cmp);
+}
+
+/// Rearrange the slice into a heap whose root is the greatest item under
+/// `cmp`.
+pub fn heapify_by<T, F>(vec: &mut [T], mut cmp: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    for root in (0..vec.len() / 2).rev() {
+        sift_down_by(vec, root, &mut cmp);
+    }
+}
+
+/// Add an item to the heap in `vec`.
+pub fn push_by<T, F>(vec: &mut Vec<T>, item: T, mut cmp: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let pos = vec.len();
+    vec.push(item);
+    sift_up_by(vec, pos, &mut cmp);
+}
+
+/// Remove and return the root of the heap in `vec`.
+pub fn pop_by<T, F>(vec: &mut Vec<T>, mut cmp: F) -> Option<T>
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    if vec.is_empty() {
+        return None;
+    }
+    let last = vec.len() - 1;
+    vec.swap(0, last);
+    let root = vec.pop();
+    sift_down_by(vec, 0, &mut cmp);
+    root
+}
+
+/// Return true if no item in the slice is greater than its parent.
+pub fn is_heap_by<T, F>(vec: &[T], mut cmp: F) -> bool
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    (1..vec.len()).all(|i| cmp(&vec[(i - 1) / 2], &vec[i]) != Ordering::Less)
+}
+
+/// Identifies an item pushed onto a [`PriorityQueue`].
+///
+/// The queue reuses the slots of items that have left it, but each reuse
+/// bumps the slot's generation, so a handle to an item that has left stays
+/// stale even after its slot holds a new item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Handle {
+    slot: usize,
+    generation: u64,
+}
+
+// Where the item of the current generation of a slot is in the heap.
+#[derive(Debug, Clone, Copy)]
+struct Slot {
+    generation: u64,
+    pos: Option<usize>,
+}
+
+/// The ways [`PriorityQueue::decrease_key`] can fail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecreaseKeyError {
+    /// The item was already popped or removed.
+    NotInQueue,
+    /// The new item is greater than the one it would replace.
+    KeyIncreased,
+}
+
+impl fmt::Display for DecreaseKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecreaseKeyError::NotInQueue => write!(f, "the item is no longer in the queue"),
+            DecreaseKeyError::KeyIncreased => {
+                write!(f, "the new key is greater than the current one")
+            }
+        }
+    }
+}
+
+impl std::error::Error for DecreaseKeyError {}
+
+/// A priority queue that pops its smallest item first.
+///
+/// ```
+/// use algos::heap::PriorityQueue;
+///
+/// let mut queue = PriorityQueue::new();
+/// queue.push(5);
+/// let task = queue.push(8);
+/// queue.decrease_key(task, 2).unwrap();
+/// assert_eq!(queue.pop(), Some(2));
+/// assert_eq!(queue.pop(), Some(5));
+/// ```
+#[derive(Debug, Clone)]
+pub struct PriorityQueue<T> {
+    entries: Vec<(T, Handle)>,
+    // One slot per handle in use or free, so this only grows to the largest
+    // number of items the queue has held at once.
+    slots: Vec<Slot>,
+    // The slots whose items have left the queue.
+    free: Vec<usize>,
+}
+
+impl<T: Ord> Default for PriorityQueue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord> PriorityQueue<T> {
+    /// Create an empty queue.
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    /// Create an empty queue with room for `capacity` items.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: Vec::with_capacity(capacity),
+            slots: Vec::with_capacity(capacity),
+            free: Vec::new(),
+        }
+    }
+
+    /// Return the number of items in the queue.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Return true if the queue holds no items.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Add an item and return a handle to it.
+    pub fn push(&mut self, item: T) -> Handle {
+        let pos = self.entries.len();
+        let slot = self.free.pop().unwrap_or_else(|| {
+            self.slots.push(Slot {
+                generation: 0,
+                pos: None,
+            });
+            self.slots.len() - 1
+        });
+        self.slots[slot].pos = Some(pos);
+        let handle = Handle {
+            slot,
+            generation: self.slots[slot].generation,
+        };
+        self.entries.push((item, handle));
+        self.restore(pos);
+        handle
+    }
+
+    /// Return the smallest item without removing it.
+    pub fn peek(&self) -> Option<&T> {
+        self.entries.first().map(|(item, _)| item)
+    }
+
+    /// Remove and return the smallest item.
+    pub fn pop(&mut self) -> Option<T> {
+        self.take(0)
+    }
+
+    /// Return the item behind `handle`, if it is still in the queue.
+    pub fn get(&self, handle: Handle) -> Option<&T> {
+        let pos = self.position(handle)?;
+        Some(&self.entries[pos].0)
+    }
+
+    /// Return true if the item behind `handle` is still in the queue.
+    pub fn contains(&self, handle: Handle) -> bool {
+        self.position(handle).is_some()
+    }
+
+    /// Replace the item behind `handle` with a smaller or equal one, and
+    /// return the old item.
+    pub fn decrease_key(&mut self, handle: Handle, item: T) -> Result<T, DecreaseKeyError> {
+        let pos = self.position(handle).ok_or(DecreaseKeyError::NotInQueue)?;
+        if item > self.entries[pos].0 {
+            return Err(DecreaseKeyError::KeyIncreased);
+        }
+        let old = std::mem::replace(&mut self.entries[pos].0, item);
+        self.restore(pos);
+        Ok(old)
+    }
+
+    /// Remove and return the item behind `handle`, if it is still in the
+    /// queue.
+    pub fn remove(&mut self, handle: Handle) -> Option<T> {
+        let pos = self.position(handle)?;
+        self.take(pos)
+    }
+
+    /// Remove every item. Handles to them become stale.
+    pub fn clear(&mut self) {
+        for (_, handle) in self.entries.drain(..) {
+            let slot = &mut self.slots[handle.slot];
+            slot.pos = None;
+            slot.generation += 1;
+            self.free.push(handle.slot);
+        }
+    }
+
+    /// Remove every item and return them in ascending order.
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        let mut sorted = Vec::with_capacity(self.len());
+        while let Some(item) = self.pop() {
+            sorted.push(item);
+        }
+        sorted
+    }
+
+    fn position(&self, handle: Handle) -> Option<usize> {
+        let slot = self.slots.get(handle.slot)?;
+        if slot.generation != handle.generation {
+            return None;
+        }
+        slot.pos
+    }
+
+    // Remove the entry at pos by moving the last entry into its place.
+    fn take(&mut self, pos: usize) -> Option<T> {
+        if pos >= self.entries.len() {
+            return None;
+        }
+        let (item, handle) = self.entries.swap_remove(pos);
+        let slot = &mut self.slots[handle.slot];
+        slot.pos = None;
+        slot.generation += 1;
+        self.free.push(handle.slot);
+        if pos < self.entries.len() {
+            self.restore(pos);
+        }
+        Some(item)
+    }
+
+    // Sift the entry at pos up or down to where it belongs, then record the
+    // new position of every entry on the path it moved along.
+    fn restore(&mut self, pos: usize) {
+        // The root of the underlying heap is the greatest entry, so compare
+        // in reverse to pop the smallest item first.
+        let mut cmp = |a: &(T, Handle), b: &(T, Handle)| b.0.cmp(&a.0);
+        let up = sift_up_by(&mut self.entries, pos, &mut cmp);
+        let (mut lower, upper) = if up < pos {
+            (pos, up)
+        } else {
+            (sift_down_by(&mut self.entries, pos, &mut cmp), pos)
+        };
+        loop {
+            let slot = self.entries[lower].1.slot;
+            self.slots[slot].pos = Some(lower);
+            if lower == upper {
+                break;
+            }
+            lower = (lower - 1) / 2;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::make_random_vec;
+    use crate::Prng;
+    use crate::Sampling;
+
+    // Check that every entry is where its slot says it is.
+    fn check_queue<T: Ord>(queue: &PriorityQueue<T>) {
+        assert!(is_heap_by(&queue.entries, |a, b| b.0.cmp(&a.0)));
+        for (pos, (_, handle)) in queue.entries.iter().enumerate() {
+            assert_eq!(queue.position(*handle), Some(pos));
+        }
+        let live = queue.slots.iter().filter(|s| s.pos.is_some()).count();
+        assert_eq!(live, queue.len());
+        assert_eq!(live + queue.free.len(), queue.slots.len());
+    }
+
+    #[test]
+    fn test_heapify() {
+        let mut vec = make_random_vec(1_000, 100);
+        heapify(&mut vec);
+        assert!(is_heap_by(&vec, i32::cmp));
+        assert_eq!(vec[0], *vec.iter().max().unwrap());
+        assert!(!is_heap_by(&[1, 2], i32::cmp));
+    }
+
+    #[test]
+    fn test_push_pop() {
+        let vec = make_random_vec(500, 1_000);
+        let mut heap = Vec::new();
+        for &x in &vec {
+            push_by(&mut heap, x, i32::cmp);
+            assert!(is_heap_by(&heap, i32::cmp));
+        }
+        let mut popped = Vec::new();
+        while let Some(x) = pop_by(&mut heap, i32::cmp) {
+            popped.push(x);
+        }
+        let mut expected = vec;
+        expected.sort_by(|a, b| b.cmp(a));
+        assert_eq!(popped, expected);
+    }
+
+    #[test]
+    fn test_queue_pops_in_order() {
+        let vec = make_random_vec(1_000, 100);
+        let mut queue = PriorityQueue::new();
+        for &x in &vec {
+            queue.push(x);
+        }
+        check_queue(&queue);
+        assert_eq!(queue.peek(), vec.iter().min());
+        let mut expected = vec;
+        expected.sort();
+        assert_eq!(queue.into_sorted_vec(), expected);
+    }
+
+    #[test]
+    fn test_decrease_key() {
+        let mut queue = PriorityQueue::new();
+        let handles: Vec<Handle> = (0..100).map(|x| queue.push(x * 10)).collect();
+        assert_eq!(queue.decrease_key(handles[50], 5), Ok(500));
+        assert_eq!(queue.get(handles[50]), Some(&5));
+        check_queue(&queue);
+        assert_eq!(
+            queue.decrease_key(handles[3], 31),
+            Err(DecreaseKeyError::KeyIncreased)
+        );
+
+        assert_eq!(queue.pop(), Some(0));
+        assert_eq!(queue.pop(), Some(5));
+        assert!(!queue.contains(handles[50]));
+        assert_eq!(
+            queue.decrease_key(handles[50], 0),
+            Err(DecreaseKeyError::NotInQueue)
+        );
+        assert_eq!(queue.get(handles[50]), None);
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut prng = Prng::with_seed(22);
+        let mut queue = PriorityQueue::new();
+        let mut handles: Vec<(Handle, i32)> = make_random_vec(300, 50)
+            .into_iter()
+            .map(|x| (queue.push(x), x))
+            .collect();
+        prng.shuffle(&mut handles);
+        let (removed, kept) = handles.split_at(150);
+        for &(handle, x) in removed {
+            assert_eq!(queue.remove(handle), Some(x));
+            assert_eq!(queue.remove(handle), None);
+            check_queue(&queue);
+        }
+        let mut expected: Vec<i32> = kept.iter().map(|&(_, x)| x).collect();
+        expected.sort();
+        assert_eq!(queue.into_sorted_vec(), expected);
+    }
+
+    #[test]
+    fn test_random_operations() {
+        // Mirror the queue with a sorted list of (item, handle) pairs.
+        let mut prng = Prng::with_seed(2022);
+        let mut queue = PriorityQueue::new();
+        let mut model: Vec<(i32, Handle)> = Vec::new();
+        for _ in 0..2_000 {
+            match prng.index(4) {
+                0 | 1 => {
+                    let x = prng.index(1_000) as i32;
+                    model.push((x, queue.push(x)));
+                }
+                2 => {
+                    let min = model.iter().map(|&(x, _)| x).min();
+                    let popped = queue.pop();
+                    assert_eq!(popped, min);
+                    if let Some(pos) = model
+                        .iter()
+                        .position(|&(x, h)| Some(x) == popped && !queue.contains(h))
+                    {
+                        model.remove(pos);
+                    }
+                }
+                _ => {
+                    if let Some(&(x, handle)) = prng.choose(&model) {
+                        let lower = x - prng.index(100) as i32;
+                        assert_eq!(queue.decrease_key(handle, lower), Ok(x));
+                        let entry = model.iter_mut().find(|(_, h)| *h == handle).unwrap();
+                        entry.0 = lower;
+                    }
+                }
+            }
+            check_queue(&queue);
+        }
+        let mut expected: Vec<i32> = model.into_iter().map(|(x, _)| x).collect();
+        expected.sort();
+        assert_eq!(queue.into_sorted_vec(), expected);
+    }
+
+    #[test]
+    fn test_slots_are_reused() {
+        let mut queue = PriorityQueue::new();
+        let mut handles: Vec<Handle> = (0..10).map(|x| queue.push(x)).collect();
+        for round in 0..1_000 {
+            // Keep the queue at ten items while cycling through many more.
+            let popped = queue.pop().unwrap();
+            assert!(!queue.contains(handles[0]));
+            handles.remove(0);
+            handles.push(queue.push(popped + 10));
+            assert_eq!(queue.get(handles[9]), Some(&(round + 10)));
+        }
+        assert_eq!(queue.slots.len(), 10);
+        check_queue(&queue);
+    }
+
+    #[test]
+    fn test_stale_handles_are_rejected() {
+        let mut queue = PriorityQueue::new();
+        let old = queue.push(1);
+        assert_eq!(queue.remove(old), Some(1));
+        // The new item takes over the slot, but not the old handle.
+        let new = queue.push(2);
+        assert_ne!(old, new);
+        assert_eq!(queue.get(old), None);
+        assert_eq!(queue.remove(old), None);
+        assert_eq!(
+            queue.decrease_key(old, 0),
+            Err(DecreaseKeyError::NotInQueue)
+        );
+        assert_eq!(queue.get(new), Some(&2));
+
+        queue.push(3);
+        queue.clear();
+        assert!(queue.is_empty());
+        assert!(!queue.contains(new));
+        check_queue(&queue);
+        let again = queue.push(4);
+        assert_eq!(queue.slots.len(), 2);
+        assert_eq!(queue.pop(), Some(4));
+        assert!(!queue.contains(again));
+    }
+}
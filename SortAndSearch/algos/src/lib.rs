@@ -0,0 +1,23 @@
+//! Sorting and searching algorithms shared by the SortAndSearch binaries.
+
+pub mod cli;
+pub mod csv;
+pub mod distribution;
+pub mod heap;
+pub mod prng;
+pub mod records;
+pub mod search;
+pub mod sort;
+pub mod total_order;
+pub mod util;
+
+pub use distribution::Distribution;
+pub use heap::PriorityQueue;
+pub use prng::{Prng, RandomSource, Sampling};
+pub use search::{binary_search, linear_search};
+pub use sort::{
+    bottom_up_merge_sort, bubble_sort, counting_sort, counting_sort_by_key, heap_sort,
+    insertion_sort, lsd_radix_sort, merge_sort, msd_radix_sort, natural_merge_sort, par_quick_sort,
+    par_quick_sort_by, quick_sort, quick_sort_by, quick_sort_by_key, tim_sort,
+};
+pub use total_order::TotalF64;
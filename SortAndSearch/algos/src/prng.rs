@@ -0,0 +1,263 @@
+//! Pseudorandom number generators.
+//!
+//! Every generator implements [`RandomSource`], so helpers such as
+//! [`make_random_vec_with`](crate::util::make_random_vec_with) accept any of
+//! them. [`Prng`] wraps the recommended default and handles seeding, and
+//! [`Sampling`] adds shuffling and sampling to every source.
+
+use std::env;
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub mod lcg;
+pub mod pcg;
+pub mod quality;
+pub mod sample;
+pub mod splitmix;
+pub mod xorshift;
+
+pub use lcg::Lcg;
+pub use pcg::Pcg32;
+pub use sample::{Reservoir, Sampling, WeightError};
+pub use splitmix::SplitMix64;
+pub use xorshift::XorShift128Plus;
+
+/// The environment variable that overrides the seed of [`Prng::new`].
+pub const SEED_VAR: &str = "PRNG_SEED";
+
+/// A source of uniformly distributed random bits.
+///
+/// Implementors only need [`next_u32`](Self::next_u32); the other methods
+/// derive from it.
+pub trait RandomSource {
+    /// Return 32 uniformly random bits.
+    fn next_u32(&mut self) -> u32;
+
+    /// Return 64 uniformly random bits.
+    fn next_u64(&mut self) -> u64 {
+        let hi = self.next_u32() as u64;
+        let lo = self.next_u32() as u64;
+        hi << 32 | lo
+    }
+
+    /// Return a pseudorandom value in the range [0.0, 1.0).
+    fn next_f64(&mut self) -> f64 {
+        // Use the top 53 bits, which is all an f64 mantissa can hold.
+        (self.next_u64() >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
+    }
+
+    /// Return a uniformly distributed value in the range [0, bound).
+    ///
+    /// Uses Lemire's multiply-and-reject method, which has no modulo bias.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bound` is zero.
+    fn below(&mut self, bound: u32) -> u32 {
+        assert!(bound > 0, "bound must be positive");
+        let mut m = self.next_u32() as u64 * bound as u64;
+        if (m as u32) < bound {
+            // Reject the few low products that would favour small results.
+            let threshold = bound.wrapping_neg() % bound;
+            while (m as u32) < threshold {
+                m = self.next_u32() as u64 * bound as u64;
+            }
+        }
+        (m >> 32) as u32
+    }
+
+    /// Return a uniformly distributed value in the range [min, max), or
+    /// `min` if the range is empty.
+    fn next_i32(&mut self, min: i32, max: i32) -> i32 {
+        if max <= min {
+            return min;
+        }
+        let range = (max as i64 - min as i64) as u32;
+        min.wrapping_add(self.below(range) as i32)
+    }
+}
+
+// ************
+// *** Prng ***
+// ************
+/// The default generator, currently [`Pcg32`], together with its seed.
+///
+/// Two generators created with the same seed produce the same sequence.
+pub struct Prng {
+    source: Pcg32,
+    initial_seed: u64,
+}
+
+impl Prng {
+    /// Create a generator seeded from `PRNG_SEED` if it is set, or from the
+    /// current time otherwise.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `PRNG_SEED` is set but is not a valid `u64`. The binaries
+    /// check it with [`env_seed`] first, so they report it instead.
+    pub fn new() -> Self {
+        let seed = env_seed().unwrap_or_else(|err| panic!("{err}"));
+        Self::with_seed(seed.unwrap_or_else(time_seed))
+    }
+
+    /// Create a generator with a fixed seed.
+    ///
+    /// The seed is a `u64` rather than a `u32` so that every seed
+    /// [`time_seed`] produces, which already needs more than 32 bits, can be
+    /// replayed exactly.
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            source: Pcg32::with_seed(seed),
+            initial_seed: seed,
+        }
+    }
+
+    /// Return the seed this generator started from, so the run can be
+    /// replayed with [`Prng::with_seed`] or `PRNG_SEED`.
+    pub fn initial_seed(&self) -> u64 {
+        self.initial_seed
+    }
+}
+
+impl Default for Prng {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RandomSource for Prng {
+    fn next_u32(&mut self) -> u32 {
+        self.source.next_u32()
+    }
+}
+
+/// The error returned when `PRNG_SEED` is set but is not a valid `u64`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeedError {
+    pub value: String,
+}
+
+impl fmt::Display for SeedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "invalid {SEED_VAR} '{}': must be an integer in [0, {}]",
+            self.value,
+            u64::MAX
+        )
+    }
+}
+
+impl std::error::Error for SeedError {}
+
+/// Return the seed given in `PRNG_SEED`, or `None` if it is not set.
+pub fn env_seed() -> Result<Option<u64>, SeedError> {
+    match env::var(SEED_VAR) {
+        Ok(value) => parse_seed(&value).map(Some),
+        Err(_) => Ok(None),
+    }
+}
+
+// Parse the value of PRNG_SEED.
+fn parse_seed(value: &str) -> Result<u64, SeedError> {
+    value.trim().parse().map_err(|_| SeedError {
+        value: value.to_string(),
+    })
+}
+
+/// Return a seed taken from the current time in milliseconds.
+pub fn time_seed() -> u64 {
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_millis();
+    millis as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A source that replays a fixed list of values.
+    struct Replay(Vec<u32>);
+
+    impl RandomSource for Replay {
+        fn next_u32(&mut self) -> u32 {
+            self.0.remove(0)
+        }
+    }
+
+    #[test]
+    fn test_with_seed_is_reproducible() {
+        let mut a = Prng::with_seed(42);
+        let mut b = Prng::with_seed(42);
+        for _ in 0..100 {
+            assert_eq!(a.next_u32(), b.next_u32());
+        }
+        assert_eq!(a.initial_seed(), 42);
+    }
+
+    #[test]
+    fn test_different_seeds_differ() {
+        let mut a = Prng::with_seed(1);
+        let mut b = Prng::with_seed(2);
+        let a: Vec<u32> = (0..10).map(|_| a.next_u32()).collect();
+        let b: Vec<u32> = (0..10).map(|_| b.next_u32()).collect();
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_parse_seed() {
+        assert_eq!(parse_seed(" 42\n"), Ok(42));
+        assert_eq!(parse_seed(&u64::MAX.to_string()), Ok(u64::MAX));
+        for bad in ["", "-1", "seed", "18446744073709551616"] {
+            assert_eq!(
+                parse_seed(bad),
+                Err(SeedError {
+                    value: bad.to_string()
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn test_below_rejects_biased_values() {
+        // With bound 3, 2^32 mod 3 = 1 low product must be rejected: a draw
+        // of 0 gives m = 0, whose low half is below the threshold of 1.
+        let mut source = Replay(vec![0, u32::MAX]);
+        assert_eq!(source.below(3), 2);
+        assert!(source.0.is_empty());
+    }
+
+    #[test]
+    fn test_below_stays_in_range() {
+        let mut prng = Prng::with_seed(3);
+        for bound in [1, 2, 3, 7, 1000, u32::MAX] {
+            for _ in 0..1000 {
+                assert!(prng.below(bound) < bound);
+            }
+        }
+    }
+
+    #[test]
+    fn test_next_i32_extremes() {
+        let mut prng = Prng::with_seed(5);
+        for _ in 0..1000 {
+            let x = prng.next_i32(i32::MIN, i32::MAX);
+            assert!(x < i32::MAX);
+            let y = prng.next_i32(-3, 3);
+            assert!((-3..3).contains(&y));
+        }
+        assert_eq!(prng.next_i32(4, 4), 4);
+        assert_eq!(prng.next_i32(4, 1), 4);
+    }
+
+    #[test]
+    fn test_next_f64_range() {
+        let mut source = Replay(vec![u32::MAX, u32::MAX, 0, 0]);
+        let high = source.next_f64();
+        assert!(high < 1.0 && high > 0.999_999);
+        assert_eq!(source.next_f64(), 0.0);
+    }
+}
@@ -0,0 +1,52 @@
+use super::RandomSource;
+
+/// The original linear congruential generator,
+/// `seed = (seed * 1103515245 + 12345) mod 2^31`.
+///
+/// Its low bits repeat with short periods, so [`RandomSource::next_u32`]
+/// only uses the top 16 of each 31-bit output. Kept for comparison; prefer
+/// [`Pcg32`](super::Pcg32).
+pub struct Lcg {
+    seed: u32,
+}
+
+impl Lcg {
+    /// Create a generator with a fixed seed. Only the low 32 bits are used.
+    pub fn with_seed(seed: u64) -> Self {
+        Self { seed: seed as u32 }
+    }
+
+    /// Return the raw output in the range [0, 2147483647].
+    pub fn next_raw(&mut self) -> u32 {
+        self.seed = self.seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+        self.seed %= 1 << 31;
+        self.seed
+    }
+}
+
+impl RandomSource for Lcg {
+    fn next_u32(&mut self) -> u32 {
+        let hi = self.next_raw() >> 15;
+        let lo = self.next_raw() >> 15;
+        hi << 16 | lo
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_known_sequence() {
+        // The first values for seed 0 pin down the generator's constants.
+        let mut lcg = Lcg::with_seed(0);
+        assert_eq!(lcg.next_raw(), 12_345);
+        assert_eq!(lcg.next_raw(), 1_406_932_606);
+    }
+
+    #[test]
+    fn test_next_u32_uses_high_bits() {
+        let mut lcg = Lcg::with_seed(0);
+        assert_eq!(lcg.next_u32(), 1_406_932_606 >> 15);
+    }
+}
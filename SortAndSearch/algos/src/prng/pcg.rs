@@ -0,0 +1,55 @@
+use super::RandomSource;
+
+const MULTIPLIER: u64 = 6_364_136_223_846_793_005;
+const DEFAULT_STREAM: u64 = 0xda3e_39cb_94b9_5bdb;
+
+/// O'Neill's PCG32 (XSH RR): a 64-bit LCG whose output is scrambled with an
+/// xorshift and a random rotation. Small, fast and statistically strong.
+pub struct Pcg32 {
+    state: u64,
+    inc: u64,
+}
+
+impl Pcg32 {
+    /// Create a generator with a fixed seed on the default stream.
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_seed_and_stream(seed, DEFAULT_STREAM)
+    }
+
+    /// Create a generator with a fixed seed on one of 2^63 independent
+    /// streams.
+    pub fn with_seed_and_stream(seed: u64, stream: u64) -> Self {
+        let mut pcg = Self {
+            state: 0,
+            inc: stream << 1 | 1,
+        };
+        pcg.next_u32();
+        pcg.state = pcg.state.wrapping_add(seed);
+        pcg.next_u32();
+        pcg
+    }
+}
+
+impl RandomSource for Pcg32 {
+    fn next_u32(&mut self) -> u32 {
+        let old = self.state;
+        self.state = old.wrapping_mul(MULTIPLIER).wrapping_add(self.inc);
+        let xorshifted = (((old >> 18) ^ old) >> 27) as u32;
+        let rot = (old >> 59) as u32;
+        xorshifted.rotate_right(rot)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reference_output() {
+        // From the pcg32-demo of the reference C implementation.
+        let mut pcg = Pcg32::with_seed_and_stream(42, 54);
+        assert_eq!(pcg.next_u32(), 0xa15c_02b7);
+        assert_eq!(pcg.next_u32(), 0x7b47_f409);
+        assert_eq!(pcg.next_u32(), 0xba1d_3330);
+    }
+}
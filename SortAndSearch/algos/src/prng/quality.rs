@@ -0,0 +1,377 @@
+//! Statistical tests for random sources.
+//!
+//! Each test draws a sample from a [`RandomSource`], computes a statistic
+//! and turns it into a p-value: the chance that a perfect generator would
+//! look at least this unusual. Values very close to 0 point to a flawed
+//! generator; values very close to 1 in the chi-squared tests mean the
+//! output is suspiciously even.
+
+use std::fmt;
+
+use super::RandomSource;
+use crate::quick_sort;
+
+/// The outcome of one statistical test.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TestResult {
+    pub name: &'static str,
+    pub statistic: f64,
+    pub p_value: f64,
+}
+
+impl TestResult {
+    /// Return true unless the p-value falls below the significance level.
+    pub fn passes(&self, alpha: f64) -> bool {
+        self.p_value >= alpha
+    }
+}
+
+impl fmt::Display for TestResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{:<24} statistic {:>12.4}  p = {:.6}",
+            self.name, self.statistic, self.p_value
+        )
+    }
+}
+
+/// Run every test with its default sample size.
+pub fn run_all<R: RandomSource + ?Sized>(rng: &mut R) -> Vec<TestResult> {
+    vec![
+        chi_squared_u32_high(rng, 256, 100_000),
+        chi_squared_u32_low(rng, 256, 100_000),
+        chi_squared_f64(rng, 100, 100_000),
+        chi_squared_i32(rng, 37, 100_000),
+        serial_correlation(rng, 100_000),
+        runs(rng, 100_000),
+        birthday_spacings(rng, 500),
+    ]
+}
+
+/// Chi-squared test on the top bits of `next_u32`. `buckets` must be a
+/// power of two.
+pub fn chi_squared_u32_high<R>(rng: &mut R, buckets: usize, samples: usize) -> TestResult
+where
+    R: RandomSource + ?Sized,
+{
+    let shift = 32 - buckets.ilog2();
+    chi_squared("chi-squared u32 high", buckets, samples, || {
+        (rng.next_u32() as u64 >> shift) as usize
+    })
+}
+
+/// Chi-squared test on the bottom bits of `next_u32`. `buckets` must be a
+/// power of two.
+pub fn chi_squared_u32_low<R>(rng: &mut R, buckets: usize, samples: usize) -> TestResult
+where
+    R: RandomSource + ?Sized,
+{
+    let mask = buckets as u32 - 1;
+    chi_squared("chi-squared u32 low", buckets, samples, || {
+        (rng.next_u32() & mask) as usize
+    })
+}
+
+/// Chi-squared test on `next_f64` split into equal-width buckets.
+pub fn chi_squared_f64<R>(rng: &mut R, buckets: usize, samples: usize) -> TestResult
+where
+    R: RandomSource + ?Sized,
+{
+    chi_squared("chi-squared f64", buckets, samples, || {
+        (rng.next_f64() * buckets as f64) as usize
+    })
+}
+
+/// Chi-squared test on `next_i32` over a range of `buckets` values.
+pub fn chi_squared_i32<R>(rng: &mut R, buckets: usize, samples: usize) -> TestResult
+where
+    R: RandomSource + ?Sized,
+{
+    let min = -(buckets as i32) / 2;
+    chi_squared("chi-squared i32", buckets, samples, || {
+        (rng.next_i32(min, min + buckets as i32) - min) as usize
+    })
+}
+
+// Count which bucket each sample falls into and compare the counts with the
+// uniform expectation.
+fn chi_squared(
+    name: &'static str,
+    buckets: usize,
+    samples: usize,
+    mut bucket: impl FnMut() -> usize,
+) -> TestResult {
+    let mut counts = vec![0usize; buckets];
+    for _ in 0..samples {
+        counts[bucket()] += 1;
+    }
+    let expected = vec![samples as f64 / buckets as f64; buckets];
+    goodness_of_fit(name, &counts, &expected)
+}
+
+/// Compare observed bucket counts with their expected values using
+/// Pearson's chi-squared test.
+///
+/// # Panics
+///
+/// Panics if the slices differ in length or there are fewer than two buckets.
+pub fn goodness_of_fit(name: &'static str, observed: &[usize], expected: &[f64]) -> TestResult {
+    assert_eq!(observed.len(), expected.len(), "bucket counts differ");
+    assert!(observed.len() >= 2, "need at least two buckets");
+    let statistic = observed
+        .iter()
+        .zip(expected)
+        .map(|(&c, &e)| (c as f64 - e).powi(2) / e)
+        .sum();
+    let dof = (observed.len() - 1) as f64;
+    TestResult {
+        name,
+        statistic,
+        p_value: gamma_q(dof / 2.0, statistic / 2.0),
+    }
+}
+
+/// Test successive `next_f64` values for lag-one correlation.
+///
+/// The statistic is the correlation coefficient, which is approximately
+/// normal with variance 1/n for independent values.
+pub fn serial_correlation<R: RandomSource + ?Sized>(rng: &mut R, samples: usize) -> TestResult {
+    let values: Vec<f64> = (0..samples).map(|_| rng.next_f64()).collect();
+    let n = samples as f64;
+    let sum: f64 = values.iter().sum();
+    let sum_sq: f64 = values.iter().map(|u| u * u).sum();
+    let sum_lag: f64 =
+        values.windows(2).map(|w| w[0] * w[1]).sum::<f64>() + values[samples - 1] * values[0];
+    let r = (n * sum_lag - sum * sum) / (n * sum_sq - sum * sum);
+
+    let mean = -1.0 / (n - 1.0);
+    let z = (r - mean) * n.sqrt();
+    TestResult {
+        name: "serial correlation",
+        statistic: r,
+        p_value: normal_two_sided(z),
+    }
+}
+
+/// Wald–Wolfowitz runs test on whether `next_f64` values lie above or below
+/// one half.
+pub fn runs<R: RandomSource + ?Sized>(rng: &mut R, samples: usize) -> TestResult {
+    let above: Vec<bool> = (0..samples).map(|_| rng.next_f64() >= 0.5).collect();
+    let n1 = above.iter().filter(|&&a| a).count() as f64;
+    let n2 = samples as f64 - n1;
+    let num_runs = 1 + above.windows(2).filter(|w| w[0] != w[1]).count();
+
+    let n = samples as f64;
+    let mean = 2.0 * n1 * n2 / n + 1.0;
+    let variance = (mean - 1.0) * (mean - 2.0) / (n - 1.0);
+    let z = (num_runs as f64 - mean) / variance.sqrt();
+    TestResult {
+        name: "runs",
+        statistic: num_runs as f64,
+        p_value: normal_two_sided(z),
+    }
+}
+
+/// Marsaglia's birthday spacings test on the top 24 bits of `next_u32`.
+///
+/// Each trial draws 512 birthdays in a year of 2^24 days and counts the
+/// repeated spacings between sorted birthdays, which should follow a
+/// Poisson distribution with mean 2. The statistic is the total over all
+/// trials.
+pub fn birthday_spacings<R: RandomSource + ?Sized>(rng: &mut R, trials: usize) -> TestResult {
+    const BIRTHDAYS: usize = 512;
+    const DAYS_BITS: u32 = 24;
+    let lambda = (BIRTHDAYS as f64).powi(3) / (4.0 * (1u64 << DAYS_BITS) as f64);
+
+    let mut total = 0;
+    for _ in 0..trials {
+        let mut days: Vec<u32> = (0..BIRTHDAYS)
+            .map(|_| rng.next_u32() >> (32 - DAYS_BITS))
+            .collect();
+        quick_sort(&mut days);
+        let mut spacings: Vec<u32> = days.windows(2).map(|w| w[1] - w[0]).collect();
+        spacings.push(days[0]);
+        quick_sort(&mut spacings);
+        total += spacings.windows(2).filter(|w| w[0] == w[1]).count();
+    }
+
+    TestResult {
+        name: "birthday spacings",
+        statistic: total as f64,
+        p_value: poisson_two_sided(total, lambda * trials as f64),
+    }
+}
+
+// Two-sided p-value of a standard normal z-score.
+fn normal_two_sided(z: f64) -> f64 {
+    // erfc(|z| / √2) = Q(1/2, z² / 2)
+    gamma_q(0.5, z * z / 2.0)
+}
+
+// Two-sided p-value of observing k from a Poisson distribution with mean mu.
+fn poisson_two_sided(k: usize, mu: f64) -> f64 {
+    // P(X <= k) = Q(k + 1, mu) and P(X >= k) = P(k, mu).
+    let at_most = gamma_q(k as f64 + 1.0, mu);
+    let at_least = if k == 0 {
+        1.0
+    } else {
+        1.0 - gamma_q(k as f64, mu)
+    };
+    (2.0 * at_most.min(at_least)).min(1.0)
+}
+
+// The upper regularized incomplete gamma function Q(a, x).
+fn gamma_q(a: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        1.0
+    } else if x < a + 1.0 {
+        1.0 - gamma_p_series(a, x)
+    } else {
+        gamma_q_continued_fraction(a, x)
+    }
+}
+
+// P(a, x) by its power series, which converges quickly for x < a + 1.
+fn gamma_p_series(a: f64, x: f64) -> f64 {
+    let mut term = 1.0 / a;
+    let mut sum = term;
+    let mut n = a;
+    for _ in 0..1000 {
+        n += 1.0;
+        term *= x / n;
+        sum += term;
+        if term.abs() < sum.abs() * 1e-15 {
+            break;
+        }
+    }
+    sum * (-x + a * x.ln() - ln_gamma(a)).exp()
+}
+
+// Q(a, x) by Lentz's continued fraction, which converges for x >= a + 1.
+fn gamma_q_continued_fraction(a: f64, x: f64) -> f64 {
+    const TINY: f64 = 1e-300;
+    let mut b = x + 1.0 - a;
+    let mut c = 1.0 / TINY;
+    let mut d = 1.0 / b;
+    let mut h = d;
+    for i in 1..1000 {
+        let an = -(i as f64) * (i as f64 - a);
+        b += 2.0;
+        d = an * d + b;
+        if d.abs() < TINY {
+            d = TINY;
+        }
+        c = b + an / c;
+        if c.abs() < TINY {
+            c = TINY;
+        }
+        d = 1.0 / d;
+        let delta = d * c;
+        h *= delta;
+        if (delta - 1.0).abs() < 1e-15 {
+            break;
+        }
+    }
+    (-x + a * x.ln() - ln_gamma(a)).exp() * h
+}
+
+// ln Γ(x) for x > 0 by the Lanczos approximation.
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 6] = [
+        76.180_091_729_471_46,
+        -86.505_320_329_416_77,
+        24.014_098_240_830_91,
+        -1.231_739_572_450_155,
+        0.120_865_097_386_617_9e-2,
+        -0.539_523_938_495_3e-5,
+    ];
+    let tmp = x + 5.5 - (x + 0.5) * (x + 5.5).ln();
+    let series: f64 = COEFFICIENTS
+        .iter()
+        .enumerate()
+        .map(|(i, c)| c / (x + 1.0 + i as f64))
+        .sum();
+    -tmp + (2.506_628_274_631_000_5 * (1.000_000_000_190_015 + series) / x).ln()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prng::{Lcg, Pcg32, Prng, SplitMix64, XorShift128Plus};
+
+    // Any single test may fail by chance at this rate; with fixed seeds
+    // the outcome is deterministic anyway.
+    const ALPHA: f64 = 1e-4;
+
+    // Reference-style source that is obviously broken: a counter.
+    struct Counter(u32);
+
+    impl RandomSource for Counter {
+        fn next_u32(&mut self) -> u32 {
+            self.0 = self.0.wrapping_add(0x0101_0101);
+            self.0
+        }
+    }
+
+    fn assert_passes(name: &str, rng: &mut dyn RandomSource) {
+        for result in run_all(rng) {
+            assert!(result.passes(ALPHA), "{name}: {result}");
+        }
+    }
+
+    #[test]
+    fn test_prng_quality() {
+        assert_passes("Prng", &mut Prng::with_seed(1));
+    }
+
+    #[test]
+    fn test_pcg32_quality() {
+        assert_passes("Pcg32", &mut Pcg32::with_seed(2));
+    }
+
+    #[test]
+    fn test_splitmix64_quality() {
+        assert_passes("SplitMix64", &mut SplitMix64::with_seed(3));
+    }
+
+    #[test]
+    fn test_xorshift128plus_quality() {
+        assert_passes("XorShift128Plus", &mut XorShift128Plus::with_seed(4));
+    }
+
+    #[test]
+    fn test_lcg_quality() {
+        assert_passes("Lcg", &mut Lcg::with_seed(5));
+    }
+
+    #[test]
+    fn test_detects_broken_source() {
+        let results = run_all(&mut Counter(0));
+        assert!(results.iter().any(|r| !r.passes(ALPHA)));
+    }
+
+    #[test]
+    fn test_gamma_q() {
+        // Q(1, x) = e^-x
+        assert!((gamma_q(1.0, 2.0) - (-2.0f64).exp()).abs() < 1e-12);
+        // Chi-squared with 2 degrees of freedom at its median 2·ln 2.
+        assert!((gamma_q(1.0, 2f64.ln()) - 0.5).abs() < 1e-12);
+        // erfc(1) = 0.157299207050285...
+        assert!((normal_two_sided(2f64.sqrt()) - 0.157_299_207_050_285).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_ln_gamma() {
+        // Γ(5) = 24 and Γ(1/2) = √π.
+        assert!((ln_gamma(5.0) - 24f64.ln()).abs() < 1e-10);
+        assert!((ln_gamma(0.5) - std::f64::consts::PI.sqrt().ln()).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_poisson_two_sided() {
+        assert!(poisson_two_sided(1000, 1000.0) > 0.9);
+        assert!(poisson_two_sided(800, 1000.0) < 1e-6);
+        assert!(poisson_two_sided(1200, 1000.0) < 1e-6);
+    }
+}
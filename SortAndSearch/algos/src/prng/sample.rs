@@ -0,0 +1,413 @@
+//! Shuffling and sampling on top of any [`RandomSource`].
+
+use std::collections::HashMap;
+use std::fmt;
+
+use super::RandomSource;
+
+/// Shuffling and sampling methods, available on every random source.
+///
+/// ```
+/// use algos::prng::{Prng, Sampling};
+///
+/// let mut prng = Prng::with_seed(1);
+/// let mut deck: Vec<u32> = (0..52).collect();
+/// prng.shuffle(&mut deck);
+/// let hand = prng.sample(&deck, 5);
+/// assert_eq!(hand.len(), 5);
+/// ```
+pub trait Sampling: RandomSource {
+    /// Return a uniformly distributed index in the range [0, bound).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bound` is zero.
+    fn index(&mut self, bound: usize) -> usize {
+        match u32::try_from(bound) {
+            Ok(bound) => self.below(bound) as usize,
+            Err(_) => {
+                // Lemire's method again, with 64-bit draws.
+                let bound = bound as u64;
+                let threshold = bound.wrapping_neg() % bound;
+                loop {
+                    let m = self.next_u64() as u128 * bound as u128;
+                    if m as u64 >= threshold {
+                        return (m >> 64) as usize;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Put the items in a uniformly random order with the Fisher-Yates
+    /// shuffle.
+    fn shuffle<T>(&mut self, slice: &mut [T]) {
+        for i in (1..slice.len()).rev() {
+            let j = self.index(i + 1);
+            slice.swap(i, j);
+        }
+    }
+
+    /// Return a uniformly chosen item, or `None` if the slice is empty.
+    fn choose<'a, T>(&mut self, slice: &'a [T]) -> Option<&'a T> {
+        if slice.is_empty() {
+            None
+        } else {
+            Some(&slice[self.index(slice.len())])
+        }
+    }
+
+    /// Return `k` distinct items in random order, or every item if there are
+    /// fewer than `k`. Each subset of that size is equally likely.
+    ///
+    /// Takes O(k) time and space, however long the slice is.
+    fn sample<'a, T>(&mut self, slice: &'a [T], k: usize) -> Vec<&'a T> {
+        let k = k.min(slice.len());
+        // Run the first k steps of a Fisher-Yates shuffle on the indices,
+        // storing only the positions that have been swapped away from
+        // holding their own index.
+        let mut moved: HashMap<usize, usize> = HashMap::with_capacity(k);
+        let mut picked = Vec::with_capacity(k);
+        for i in 0..k {
+            let j = i + self.index(slice.len() - i);
+            let at_i = moved.get(&i).copied().unwrap_or(i);
+            let at_j = moved.get(&j).copied().unwrap_or(j);
+            moved.insert(j, at_i);
+            picked.push(&slice[at_j]);
+        }
+        picked
+    }
+
+    /// Return an item chosen with probability proportional to its weight.
+    ///
+    /// Weights must be finite and non-negative, and at least one must be
+    /// positive.
+    fn choose_weighted<'a, T, F>(&mut self, slice: &'a [T], weight: F) -> Result<&'a T, WeightError>
+    where
+        F: Fn(&T) -> f64,
+    {
+        let mut total = 0.0;
+        for (index, item) in slice.iter().enumerate() {
+            let w = weight(item);
+            if !(w.is_finite() && w >= 0.0) {
+                return Err(WeightError::InvalidWeight { index, weight: w });
+            }
+            total += w;
+        }
+        if total <= 0.0 {
+            return Err(WeightError::NoPositiveWeight);
+        }
+
+        let mut target = self.next_f64() * total;
+        let mut last_positive = 0;
+        for (index, item) in slice.iter().enumerate() {
+            let w = weight(item);
+            if w > 0.0 {
+                if target < w {
+                    return Ok(item);
+                }
+                target -= w;
+                last_positive = index;
+            }
+        }
+        // Rounding can leave target just above the last weight.
+        Ok(&slice[last_positive])
+    }
+
+    /// Return `k` items chosen uniformly from a stream of unknown length,
+    /// using a [`Reservoir`].
+    fn reservoir_sample<I: IntoIterator>(&mut self, iter: I, k: usize) -> Vec<I::Item> {
+        let mut reservoir = Reservoir::new(k);
+        for item in iter {
+            reservoir.push(self, item);
+        }
+        reservoir.into_vec()
+    }
+}
+
+impl<R: RandomSource + ?Sized> Sampling for R {}
+
+/// The error returned by [`Sampling::choose_weighted`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WeightError {
+    /// A weight was negative, infinite or NaN.
+    InvalidWeight { index: usize, weight: f64 },
+    /// No item had a positive weight, including when there were no items.
+    NoPositiveWeight,
+}
+
+impl fmt::Display for WeightError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WeightError::InvalidWeight { index, weight } => {
+                write!(f, "item {index} has invalid weight {weight}")
+            }
+            WeightError::NoPositiveWeight => write!(f, "no item has a positive weight"),
+        }
+    }
+}
+
+impl std::error::Error for WeightError {}
+
+// *****************
+// *** Reservoir ***
+// *****************
+/// A uniform sample of at most `k` items from a stream, kept with
+/// Vitter's Algorithm R.
+///
+/// After `n` pushes every item seen so far is in the sample with
+/// probability k / n.
+#[derive(Debug, Clone)]
+pub struct Reservoir<T> {
+    k: usize,
+    seen: usize,
+    items: Vec<T>,
+}
+
+impl<T> Reservoir<T> {
+    /// Create an empty reservoir that keeps at most `k` items.
+    pub fn new(k: usize) -> Self {
+        Self {
+            k,
+            seen: 0,
+            items: Vec::with_capacity(k),
+        }
+    }
+
+    /// Offer the next item from the stream.
+    pub fn push<R: RandomSource + ?Sized>(&mut self, prng: &mut R, item: T) {
+        self.seen += 1;
+        if self.items.len() < self.k {
+            self.items.push(item);
+        } else {
+            let j = prng.index(self.seen);
+            if j < self.k {
+                self.items[j] = item;
+            }
+        }
+    }
+
+    /// The number of items offered so far.
+    pub fn seen(&self) -> usize {
+        self.seen
+    }
+
+    /// The current sample.
+    pub fn as_slice(&self) -> &[T] {
+        &self.items
+    }
+
+    /// Consume the reservoir and return the sample.
+    pub fn into_vec(self) -> Vec<T> {
+        self.items
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prng::quality::goodness_of_fit;
+    use crate::prng::Prng;
+
+    const ALPHA: f64 = 1e-4;
+
+    // Check that every outcome of 0..buckets turned up about equally often.
+    fn assert_uniform(name: &'static str, counts: &[usize]) {
+        let total: usize = counts.iter().sum();
+        let expected = vec![total as f64 / counts.len() as f64; counts.len()];
+        let result = goodness_of_fit(name, counts, &expected);
+        assert!(result.passes(ALPHA), "{result}");
+    }
+
+    // Number the permutations of [0, 1, 2, 3] in lexicographic order.
+    fn permutation_rank(perm: &[usize]) -> usize {
+        let mut rank = 0;
+        for i in 0..perm.len() {
+            let smaller_after = perm[i + 1..].iter().filter(|&&x| x < perm[i]).count();
+            rank = rank * (perm.len() - i) + smaller_after;
+        }
+        rank
+    }
+
+    #[test]
+    fn test_index_range() {
+        let mut prng = Prng::with_seed(1);
+        for bound in [
+            1,
+            2,
+            10,
+            u32::MAX as usize,
+            u32::MAX as usize + 7,
+            usize::MAX,
+        ] {
+            for _ in 0..100 {
+                assert!(prng.index(bound) < bound);
+            }
+        }
+    }
+
+    #[test]
+    fn test_shuffle_uniform() {
+        // All 24 permutations of four items should be equally likely.
+        let mut prng = Prng::with_seed(2);
+        let mut counts = [0; 24];
+        for _ in 0..48_000 {
+            let mut perm = [0, 1, 2, 3];
+            prng.shuffle(&mut perm);
+            counts[permutation_rank(&perm)] += 1;
+        }
+        assert_uniform("shuffle", &counts);
+    }
+
+    #[test]
+    fn test_shuffle_keeps_items() {
+        let mut prng = Prng::with_seed(3);
+        let mut vec: Vec<i32> = (0..1_000).collect();
+        prng.shuffle(&mut vec);
+        assert_ne!(vec, (0..1_000).collect::<Vec<i32>>());
+        vec.sort();
+        assert_eq!(vec, (0..1_000).collect::<Vec<i32>>());
+
+        let mut empty: [i32; 0] = [];
+        prng.shuffle(&mut empty);
+    }
+
+    #[test]
+    fn test_choose_uniform() {
+        let mut prng = Prng::with_seed(4);
+        let items: Vec<usize> = (0..10).collect();
+        let mut counts = [0; 10];
+        for _ in 0..50_000 {
+            counts[*prng.choose(&items).unwrap()] += 1;
+        }
+        assert_uniform("choose", &counts);
+        assert_eq!(prng.choose::<i32>(&[]), None);
+    }
+
+    #[test]
+    fn test_sample_uniform() {
+        // Every item of 0..10 should be picked equally often, and no sample
+        // should repeat an item.
+        let mut prng = Prng::with_seed(5);
+        let items: Vec<usize> = (0..10).collect();
+        let mut counts = [0; 10];
+        for _ in 0..20_000 {
+            let picked = prng.sample(&items, 3);
+            assert_eq!(picked.len(), 3);
+            assert!(picked[0] != picked[1] && picked[0] != picked[2] && picked[1] != picked[2]);
+            for &x in picked {
+                counts[x] += 1;
+            }
+        }
+        assert_uniform("sample", &counts);
+    }
+
+    #[test]
+    fn test_sample_order_uniform() {
+        // Taking all four items is a shuffle, so the order must be uniform too.
+        let mut prng = Prng::with_seed(6);
+        let items = [0, 1, 2, 3];
+        let mut counts = [0; 24];
+        for _ in 0..48_000 {
+            let perm: Vec<usize> = prng.sample(&items, 10).into_iter().copied().collect();
+            counts[permutation_rank(&perm)] += 1;
+        }
+        assert_uniform("sample order", &counts);
+    }
+
+    #[test]
+    fn test_sample_matches_partial_shuffle() {
+        // The sparse version must pick exactly what the first k steps of a
+        // Fisher-Yates shuffle over every index would.
+        for (len, k) in [(1, 1), (10, 3), (10, 10), (1_000, 50), (1_000, 999)] {
+            let items: Vec<usize> = (0..len).collect();
+            let mut prng = Prng::with_seed(len as u64 + k as u64);
+            let picked: Vec<usize> = prng.sample(&items, k).into_iter().copied().collect();
+
+            let mut prng = Prng::with_seed(len as u64 + k as u64);
+            let mut indices = items.clone();
+            for i in 0..k {
+                let j = i + prng.index(len - i);
+                indices.swap(i, j);
+            }
+            assert_eq!(picked, indices[..k], "{len} {k}");
+        }
+    }
+
+    #[test]
+    fn test_choose_weighted_proportions() {
+        let mut prng = Prng::with_seed(7);
+        let weights = [1.0, 0.0, 2.0, 5.0];
+        let items = [0, 1, 2, 3];
+        let mut counts = [0; 4];
+        let draws = 80_000;
+        for _ in 0..draws {
+            counts[*prng.choose_weighted(&items, |&i| weights[i]).unwrap()] += 1;
+        }
+        assert_eq!(counts[1], 0);
+
+        let observed = [counts[0], counts[2], counts[3]];
+        let expected: Vec<f64> = [1.0, 2.0, 5.0]
+            .iter()
+            .map(|w| draws as f64 * w / 8.0)
+            .collect();
+        let result = goodness_of_fit("choose_weighted", &observed, &expected);
+        assert!(result.passes(ALPHA), "{result}");
+    }
+
+    #[test]
+    fn test_choose_weighted_errors() {
+        let mut prng = Prng::with_seed(8);
+        assert_eq!(
+            prng.choose_weighted(&[1.0, -1.0], |&w| w),
+            Err(WeightError::InvalidWeight {
+                index: 1,
+                weight: -1.0
+            })
+        );
+        assert!(matches!(
+            prng.choose_weighted(&[f64::NAN], |&w| w),
+            Err(WeightError::InvalidWeight { index: 0, .. })
+        ));
+        assert_eq!(
+            prng.choose_weighted(&[0.0, 0.0], |&w| w),
+            Err(WeightError::NoPositiveWeight)
+        );
+        assert_eq!(
+            prng.choose_weighted::<f64, _>(&[], |&w| w),
+            Err(WeightError::NoPositiveWeight)
+        );
+    }
+
+    #[test]
+    fn test_reservoir_uniform() {
+        let mut prng = Prng::with_seed(9);
+        let mut counts = [0; 20];
+        for _ in 0..10_000 {
+            for x in prng.reservoir_sample(0..20, 4) {
+                counts[x] += 1;
+            }
+        }
+        assert_uniform("reservoir", &counts);
+    }
+
+    #[test]
+    fn test_reservoir_short_stream() {
+        let mut prng = Prng::with_seed(10);
+        let mut reservoir = Reservoir::new(5);
+        for x in 0..3 {
+            reservoir.push(&mut prng, x);
+        }
+        assert_eq!(reservoir.seen(), 3);
+        assert_eq!(reservoir.as_slice(), &[0, 1, 2]);
+        assert!(prng.reservoir_sample(0..10, 0).is_empty());
+    }
+
+    #[test]
+    fn test_works_on_trait_objects() {
+        let mut source: Box<dyn RandomSource> = Box::new(Prng::with_seed(11));
+        let mut vec = [1, 2, 3];
+        source.shuffle(&mut vec);
+        assert!(source.choose(&vec).is_some());
+    }
+}
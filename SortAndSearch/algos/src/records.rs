@@ -0,0 +1,450 @@
+//! Numbers and CSV rows read from text, so real data can be sorted.
+//!
+//! Input is read a line or a CSV record at a time from any [`BufRead`] and
+//! output is written the same way to any [`Write`], so files, pipes and
+//! in-memory buffers all work.
+
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+
+use crate::csv::{format_record, parse_record, CsvError, Records};
+use crate::sort::{apply_order, Algorithm, CountingSortError, Customer};
+
+/// The ways reading records can fail. Line numbers start at 1.
+#[derive(Debug)]
+pub enum ReadError {
+    /// Reading the input failed.
+    Io(io::Error),
+    /// The text where a number was expected is not an integer.
+    InvalidNumber { line: usize, text: String },
+    /// The row has fewer fields than the key column needs.
+    MissingColumn { line: usize, column: usize },
+    /// The row is not valid CSV.
+    Csv { line: usize, err: CsvError },
+}
+
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReadError::Io(err) => write!(f, "error reading input: {err}"),
+            ReadError::InvalidNumber { line, text } => {
+                write!(f, "line {line}: '{text}' is not an integer")
+            }
+            ReadError::MissingColumn { line, column } => {
+                write!(f, "line {line}: there is no column {column}")
+            }
+            ReadError::Csv { line, err } => write!(f, "line {line}: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for ReadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ReadError::Io(err) => Some(err),
+            ReadError::Csv { err, .. } => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ReadError {
+    fn from(err: io::Error) -> Self {
+        ReadError::Io(err)
+    }
+}
+
+/// Open the file for buffered reading, or stdin for `-` or no path.
+pub fn open_input(path: Option<&str>) -> io::Result<Box<dyn BufRead>> {
+    match path {
+        None | Some("-") => Ok(Box::new(io::stdin().lock())),
+        Some(path) => Ok(Box::new(BufReader::new(File::open(path)?))),
+    }
+}
+
+/// Create the file for buffered writing, or use stdout for `-` or no path.
+pub fn create_output(path: Option<&str>) -> io::Result<Box<dyn Write>> {
+    match path {
+        None | Some("-") => Ok(Box::new(BufWriter::new(io::stdout().lock()))),
+        Some(path) => Ok(Box::new(BufWriter::new(File::create(path)?))),
+    }
+}
+
+/// Read one integer per line, skipping blank lines.
+pub fn read_numbers<R: BufRead>(input: R) -> Result<Vec<i64>, ReadError> {
+    let mut numbers = Vec::new();
+    for (i, line) in input.lines().enumerate() {
+        let line = line?;
+        let text = line.trim();
+        if text.is_empty() {
+            continue;
+        }
+        match text.parse() {
+            Ok(n) => numbers.push(n),
+            Err(_) => {
+                return Err(ReadError::InvalidNumber {
+                    line: i + 1,
+                    text: text.to_string(),
+                })
+            }
+        }
+    }
+    Ok(numbers)
+}
+
+/// Write one integer per line.
+pub fn write_numbers<W: Write>(mut output: W, numbers: &[i64]) -> io::Result<()> {
+    for n in numbers {
+        writeln!(output, "{n}")?;
+    }
+    output.flush()
+}
+
+/// CSV rows keyed by the integer in one of their columns.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CsvTable {
+    /// The first row, if none of its fields held an integer.
+    pub header: Option<String>,
+    /// The data rows, exactly as they were read.
+    pub rows: Vec<String>,
+    /// The key of each row.
+    pub keys: Vec<i64>,
+}
+
+impl CsvTable {
+    /// Read CSV rows keyed by `column`, numbered from 1. Blank lines are
+    /// skipped. The first row is taken as a header if none of its fields is
+    /// an integer, so a mistyped key in a headerless file is still an error.
+    pub fn read<R: BufRead>(input: R, column: usize) -> Result<Self, ReadError> {
+        let mut table = CsvTable::default();
+        for record in Records::new(input) {
+            let record = record?;
+            if record.text.trim().is_empty() {
+                continue;
+            }
+            let (number, line) = (record.line, record.text);
+            let fields = parse_record(&line).map_err(|err| ReadError::Csv { line: number, err })?;
+            let Some(field) = column.checked_sub(1).and_then(|c| fields.get(c)) else {
+                return Err(ReadError::MissingColumn {
+                    line: number,
+                    column,
+                });
+            };
+            match field.trim().parse() {
+                Ok(key) => {
+                    table.keys.push(key);
+                    table.rows.push(line);
+                }
+                Err(_) if table.rows.is_empty() && !fields.iter().any(|f| is_integer(f)) => {
+                    table.header = Some(line);
+                }
+                Err(_) => {
+                    return Err(ReadError::InvalidNumber {
+                        line: number,
+                        text: field.clone(),
+                    })
+                }
+            }
+        }
+        Ok(table)
+    }
+
+    /// Sort the rows by key with the given algorithm.
+    pub fn sort(&mut self, algo: Algorithm) -> Result<(), CountingSortError> {
+        let mut order: Vec<(i64, usize)> = self.keys.iter().copied().zip(0..).collect();
+        algo.sort_by_key(&mut order, |&(key, _)| key)?;
+        let order: Vec<usize> = order.iter().map(|&(_, i)| i).collect();
+        apply_order(&mut self.rows, &order);
+        apply_order(&mut self.keys, &order);
+        Ok(())
+    }
+
+    /// Write the header, if any, and then the rows.
+    pub fn write<W: Write>(&self, mut output: W) -> io::Result<()> {
+        if let Some(header) = &self.header {
+            writeln!(output, "{header}")?;
+        }
+        for row in &self.rows {
+            writeln!(output, "{row}")?;
+        }
+        output.flush()
+    }
+}
+
+// Return true if the field holds an integer, ignoring surrounding spaces.
+fn is_integer(field: &str) -> bool {
+    field.trim().parse::<i64>().is_ok()
+}
+
+// *****************
+// *** Customers ***
+// *****************
+/// The header written by [`write_customers`].
+pub const CUSTOMER_HEADER: &str = "id,num_purchases";
+
+/// A problem with one row of a customer CSV file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RowError {
+    /// The line the row is on, counting from 1.
+    pub line: usize,
+    pub problem: RowProblem,
+}
+
+/// What is wrong with a customer row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RowProblem {
+    /// The row is not valid CSV.
+    Csv(CsvError),
+    /// The row does not have exactly two fields.
+    FieldCount(usize),
+    /// The purchases field is not a non-negative integer.
+    InvalidPurchases(String),
+}
+
+impl fmt::Display for RowError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match &self.problem {
+            RowProblem::Csv(err) => write!(f, "{err}"),
+            RowProblem::FieldCount(found) => {
+                write!(f, "expected 2 fields (id, num_purchases), found {found}")
+            }
+            RowProblem::InvalidPurchases(text) => {
+                write!(f, "'{text}' is not a valid number of purchases")
+            }
+        }
+    }
+}
+
+impl std::error::Error for RowError {}
+
+/// The customers read from a CSV file, and the rows that had to be skipped.
+#[derive(Debug, Clone, Default)]
+pub struct CustomerImport {
+    pub customers: Vec<Customer>,
+    pub errors: Vec<RowError>,
+    /// True if the first row was a header.
+    pub has_header: bool,
+}
+
+/// Read `id,num_purchases` rows. Blank lines are skipped.
+///
+/// The first row is taken as a header if its second field holds no digits.
+/// Malformed rows are reported in [`CustomerImport::errors`] and skipped, so
+/// only I/O failures stop the import.
+pub fn read_customers<R: BufRead>(input: R) -> io::Result<CustomerImport> {
+    let mut import = CustomerImport::default();
+    let mut first_row = true;
+    for record in Records::new(input) {
+        let record = record?;
+        if record.text.trim().is_empty() {
+            continue;
+        }
+        let is_first_row = std::mem::replace(&mut first_row, false);
+        match parse_customer(&record.text) {
+            Ok(customer) => import.customers.push(customer),
+            Err(RowProblem::InvalidPurchases(text))
+                if is_first_row && !text.contains(|c: char| c.is_ascii_digit()) =>
+            {
+                import.has_header = true;
+            }
+            Err(problem) => import.errors.push(RowError {
+                line: record.line,
+                problem,
+            }),
+        }
+    }
+    Ok(import)
+}
+
+// Parse one id,num_purchases row.
+fn parse_customer(line: &str) -> Result<Customer, RowProblem> {
+    let fields = parse_record(line).map_err(RowProblem::Csv)?;
+    let [id, purchases] = fields.as_slice() else {
+        return Err(RowProblem::FieldCount(fields.len()));
+    };
+    match purchases.trim().parse::<i32>() {
+        Ok(num_purchases) if num_purchases >= 0 => Ok(Customer {
+            id: id.clone(),
+            num_purchases,
+        }),
+        _ => Err(RowProblem::InvalidPurchases(purchases.clone())),
+    }
+}
+
+/// Write the customers as CSV, starting with [`CUSTOMER_HEADER`].
+pub fn write_customers<W: Write>(mut output: W, customers: &[Customer]) -> io::Result<()> {
+    writeln!(output, "{CUSTOMER_HEADER}")?;
+    for customer in customers {
+        let purchases = customer.num_purchases.to_string();
+        writeln!(output, "{}", format_record(&[&customer.id, &purchases]))?;
+    }
+    output.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_write_numbers() {
+        let numbers = read_numbers("3\n\n -1 \n20\n".as_bytes()).unwrap();
+        assert_eq!(numbers, [3, -1, 20]);
+
+        let mut output = Vec::new();
+        write_numbers(&mut output, &numbers).unwrap();
+        assert_eq!(output, b"3\n-1\n20\n");
+    }
+
+    #[test]
+    fn test_read_numbers_error() {
+        let err = read_numbers("1\n2\nthree\n".as_bytes()).unwrap_err();
+        assert_eq!(err.to_string(), "line 3: 'three' is not an integer");
+    }
+
+    #[test]
+    fn test_csv_table_sort() {
+        let input = "name,age\n\"Lee, A\",40\nKim,7\n\nPat,40\nSam,-2\n";
+        let mut table = CsvTable::read(input.as_bytes(), 2).unwrap();
+        assert_eq!(table.header.as_deref(), Some("name,age"));
+        assert_eq!(table.keys, [40, 7, 40, -2]);
+
+        table.sort(Algorithm::Counting).unwrap();
+        let mut output = Vec::new();
+        table.write(&mut output).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "name,age\nSam,-2\nKim,7\n\"Lee, A\",40\nPat,40\n"
+        );
+    }
+
+    #[test]
+    fn test_csv_table_without_header() {
+        let table = CsvTable::read("5,a\n1,b\n".as_bytes(), 1).unwrap();
+        assert_eq!(table.header, None);
+        assert_eq!(table.keys, [5, 1]);
+    }
+
+    #[test]
+    fn test_csv_table_bad_first_key() {
+        // A row with any integer in it is data, so the typo is reported.
+        let err = CsvTable::read("12a,x,3\n4,y,5\n".as_bytes(), 1).unwrap_err();
+        assert_eq!(err.to_string(), "line 1: '12a' is not an integer");
+    }
+
+    #[test]
+    fn test_csv_table_multiline_field() {
+        let input = "name,n\n\"two\nlines\",2\nKim,1\n";
+        let mut table = CsvTable::read(input.as_bytes(), 2).unwrap();
+        assert_eq!(table.keys, [2, 1]);
+
+        table.sort(Algorithm::Quick).unwrap();
+        let mut output = Vec::new();
+        table.write(&mut output).unwrap();
+        let text = String::from_utf8(output).unwrap();
+        assert_eq!(text, "name,n\nKim,1\n\"two\nlines\",2\n");
+        assert_eq!(CsvTable::read(text.as_bytes(), 2).unwrap(), table);
+    }
+
+    #[test]
+    fn test_csv_table_errors() {
+        let err = CsvTable::read("a,1\nb\n".as_bytes(), 2).unwrap_err();
+        assert!(matches!(
+            err,
+            ReadError::MissingColumn { line: 2, column: 2 }
+        ));
+
+        let err = CsvTable::read("id,n\na,1\nb,x\n".as_bytes(), 2).unwrap_err();
+        assert_eq!(err.to_string(), "line 3: 'x' is not an integer");
+
+        let err = CsvTable::read("a,1\n\"b,2\n".as_bytes(), 2).unwrap_err();
+        assert!(matches!(err, ReadError::Csv { line: 2, .. }));
+
+        let err = CsvTable::read("1\n".as_bytes(), 0).unwrap_err();
+        assert!(matches!(err, ReadError::MissingColumn { column: 0, .. }));
+    }
+
+    #[test]
+    fn test_read_customers() {
+        let input = "id,num_purchases\nC1,5\n\n\"Doe, Jane\",12\nC3,0\r\n";
+        let import = read_customers(input.as_bytes()).unwrap();
+        assert!(import.has_header);
+        assert!(import.errors.is_empty());
+        let rows: Vec<String> = import.customers.iter().map(|c| c.to_string()).collect();
+        assert_eq!(rows, ["(C1, 5)", "(Doe, Jane, 12)", "(C3, 0)"]);
+    }
+
+    #[test]
+    fn test_read_customers_without_header() {
+        let import = read_customers("C1,5\nC2,3\n".as_bytes()).unwrap();
+        assert!(!import.has_header);
+        assert_eq!(import.customers.len(), 2);
+    }
+
+    #[test]
+    fn test_read_customers_reports_bad_rows() {
+        let input = "C0,x7\nC1,5\nC2\nC3,-1\n\"C4,2\nC5,many\nC6,1,2\nC7,2\n";
+        let import = read_customers(input.as_bytes()).unwrap();
+        assert!(!import.has_header);
+        let ids: Vec<&str> = import.customers.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids, ["C1", "C7"]);
+
+        let errors: Vec<String> = import.errors.iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            errors,
+            [
+                "line 1: 'x7' is not a valid number of purchases",
+                "line 3: expected 2 fields (id, num_purchases), found 1",
+                "line 4: '-1' is not a valid number of purchases",
+                "line 5: field 1 has an unterminated quote",
+                "line 6: 'many' is not a valid number of purchases",
+                "line 7: expected 2 fields (id, num_purchases), found 3",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_customers_round_trip() {
+        let customers = vec![
+            Customer {
+                id: "Smith, \"Jo\"".to_string(),
+                num_purchases: 3,
+            },
+            Customer {
+                id: "C2".to_string(),
+                num_purchases: 0,
+            },
+        ];
+        let mut output = Vec::new();
+        write_customers(&mut output, &customers).unwrap();
+        let text = String::from_utf8(output).unwrap();
+        assert_eq!(text, "id,num_purchases\n\"Smith, \"\"Jo\"\"\",3\nC2,0\n");
+
+        let import = read_customers(text.as_bytes()).unwrap();
+        assert!(import.has_header);
+        assert_eq!(import.customers.len(), 2);
+        assert_eq!(import.customers[0].id, customers[0].id);
+        assert_eq!(import.customers[1].num_purchases, 0);
+    }
+
+    #[test]
+    fn test_customers_round_trip_line_break() {
+        let customers = vec![
+            Customer {
+                id: "two\nlines".to_string(),
+                num_purchases: 4,
+            },
+            Customer {
+                id: "C2".to_string(),
+                num_purchases: 1,
+            },
+        ];
+        let mut output = Vec::new();
+        write_customers(&mut output, &customers).unwrap();
+        let import = read_customers(output.as_slice()).unwrap();
+        assert!(import.errors.is_empty(), "{:?}", import.errors);
+        let ids: Vec<&str> = import.customers.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids, ["two\nlines", "C2"]);
+    }
+}
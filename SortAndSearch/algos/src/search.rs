@@ -0,0 +1,103 @@
+//! Searching algorithms.
+//!
+//! Both searches return `(position, comparisons)`, with a position of -1 if
+//! the target was not found.
+
+/// Search the slice front to back for `target`.
+pub fn linear_search(vec: &[i32], target: i32) -> (i32, i32) {
+    if let Some(pos) = vec.iter().position(|&x| x == target) {
+        return (pos as i32, (pos + 1) as i32);
+    }
+    (-1, vec.len() as i32)
+}
+
+/// Search the sorted slice for `target` by repeatedly halving the range.
+pub fn binary_search(vec: &[i32], target: i32) -> (i32, i32) {
+    if vec.is_empty() || target < vec[0] || target > vec[vec.len() - 1] {
+        return (-1, 0);
+    }
+
+    let mut high = vec.len() - 1;
+    let mut low = 0;
+    let mut test_count = 0;
+    loop {
+        test_count += 1;
+        let i = low + (high - low) / 2;
+
+        if vec[i] == target {
+            return (i as i32, test_count);
+        }
+
+        if vec[i] < target {
+            low = i;
+        } else {
+            high = i;
+        }
+
+        if (high - low) <= 1 {
+            if vec[low] == target {
+                return (low as i32, test_count + 1);
+            }
+            if vec[high] == target {
+                return (high as i32, test_count + 1);
+            }
+            return (-1, test_count);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prng::Prng;
+    use crate::quick_sort;
+    use crate::util::{make_random_vec, make_random_vec_with};
+
+    #[test]
+    fn test_linear_search() {
+        let vec = vec![1, 2, 3, 4, 5, 6, 7, 8, 9];
+        let item_to_search = 5;
+        let (pos, cmps) = linear_search(&vec, item_to_search);
+        assert_eq!(pos, 4);
+        assert_eq!(cmps, pos + 1);
+    }
+
+    #[test]
+    fn test_linear_search_big_vector() {
+        // A fixed seed whose data is known to contain the target.
+        let mut prng = Prng::with_seed(1);
+        let vec = make_random_vec_with(&mut prng, 1000, 100);
+        let item_to_search = 5;
+        let (pos, cmps) = linear_search(&vec, item_to_search);
+        assert!(pos >= 0, "{item_to_search} not found");
+        assert_eq!(vec[pos as usize], item_to_search);
+        assert!(!vec[..pos as usize].contains(&item_to_search));
+        assert_eq!(cmps, pos + 1);
+    }
+
+    #[test]
+    fn test_binary_search() {
+        let vec = vec![1, 2, 3, 4, 5, 6, 7, 8, 9];
+        let item_to_search = 5;
+        let (pos, cmps) = binary_search(&vec, item_to_search);
+        assert_eq!(pos, 4);
+        assert_eq!(cmps, 1);
+    }
+
+    #[test]
+    fn test_binary_search_big_vector() {
+        let mut vec = make_random_vec(1000, 100);
+        quick_sort(&mut vec);
+        let item_to_search = vec[500];
+        let (pos, _) = binary_search(&vec, item_to_search);
+        assert!(vec[pos as usize] == item_to_search);
+    }
+
+    #[test]
+    fn test_binary_search_not_found() {
+        let vec = vec![1, 3, 5, 7, 9];
+        assert_eq!(binary_search(&vec, 4).0, -1);
+        assert_eq!(binary_search(&vec, 10).0, -1);
+        assert_eq!(binary_search(&[], 1).0, -1);
+    }
+}
@@ -0,0 +1,49 @@
+//! Sorting algorithms.
+
+pub mod algorithm;
+pub mod bubble;
+pub mod counting;
+pub mod external;
+pub mod heap;
+pub mod insertion;
+pub mod merge;
+pub mod quick;
+pub mod radix;
+pub mod stats;
+pub mod tim;
+
+pub use algorithm::{Algorithm, ParseAlgorithmError};
+pub use bubble::{
+    bubble_sort, bubble_sort_with_stats, cocktail_shaker_sort, comb_sort, early_exit_bubble_sort,
+    last_swap_bubble_sort, BubbleVariant,
+};
+pub use counting::{
+    counting_sort, counting_sort_by_key, counting_sort_with_stats, CountingSortError, Customer,
+};
+pub use external::{ExternalSort, ExternalSortError, ExternalSortStats};
+pub use heap::heap_sort;
+pub use insertion::insertion_sort;
+pub use merge::{bottom_up_merge_sort, merge_sort, merge_sort_by, natural_merge_sort};
+pub use quick::{
+    par_quick_sort, par_quick_sort_by, quick_sort, quick_sort_by, quick_sort_by_key,
+    quick_sort_with_stats,
+};
+pub use radix::{lsd_radix_sort, lsd_radix_sort_by_key, msd_radix_sort, Radix, RadixKey};
+pub use stats::SortStats;
+pub use tim::{tim_sort, tim_sort_by, tim_sort_by_key};
+
+// Rearrange vec so that position k holds the item previously at order[k].
+pub(crate) fn apply_order<T>(vec: &mut [T], order: &[usize]) {
+    let mut dest = vec![0; order.len()];
+    for (new, &old) in order.iter().enumerate() {
+        dest[old] = new;
+    }
+    for i in 0..vec.len() {
+        // Swap the item at i into its destination until i holds its own item.
+        while dest[i] != i {
+            let j = dest[i];
+            vec.swap(i, j);
+            dest.swap(i, j);
+        }
+    }
+}
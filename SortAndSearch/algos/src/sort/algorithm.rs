@@ -0,0 +1,174 @@
+use std::fmt;
+use std::str::FromStr;
+
+use super::counting::{counting_sort_by_key, CountingSortError};
+use super::heap::heap_sort_by;
+use super::insertion::insertion_sort_by;
+use super::merge::{MergeSort, MergeVariant};
+use super::quick::quick_sort_by;
+use super::radix::{lsd_radix_sort_by_key, Radix};
+use super::tim::tim_sort_by_key;
+
+/// A sorting algorithm that can be chosen at run time, for example from the
+/// command line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Algorithm {
+    #[default]
+    Quick,
+    Heap,
+    Insertion,
+    Merge,
+    BottomUpMerge,
+    NaturalMerge,
+    Tim,
+    Counting,
+    Radix,
+}
+
+impl Algorithm {
+    /// Every algorithm.
+    pub const ALL: [Algorithm; 9] = [
+        Algorithm::Quick,
+        Algorithm::Heap,
+        Algorithm::Insertion,
+        Algorithm::Merge,
+        Algorithm::BottomUpMerge,
+        Algorithm::NaturalMerge,
+        Algorithm::Tim,
+        Algorithm::Counting,
+        Algorithm::Radix,
+    ];
+
+    /// Return true if items with equal keys keep their order.
+    pub fn is_stable(self) -> bool {
+        !matches!(self, Algorithm::Quick | Algorithm::Heap)
+    }
+
+    /// Sort the items in place by an integer key.
+    ///
+    /// Only counting sort can fail, when the keys span more values than its
+    /// count table may hold.
+    pub fn sort_by_key<T, F>(self, vec: &mut Vec<T>, key: F) -> Result<(), CountingSortError>
+    where
+        T: Copy,
+        F: Fn(&T) -> i64,
+    {
+        match self {
+            Algorithm::Quick => quick_sort_by(vec, |a, b| key(a).cmp(&key(b))),
+            Algorithm::Heap => heap_sort_by(vec, |a, b| key(a).cmp(&key(b))),
+            Algorithm::Insertion => insertion_sort_by(vec, |a, b| key(a).cmp(&key(b))),
+            Algorithm::Merge => merge(MergeVariant::TopDown, vec, key),
+            Algorithm::BottomUpMerge => merge(MergeVariant::BottomUp, vec, key),
+            Algorithm::NaturalMerge => merge(MergeVariant::Natural, vec, key),
+            Algorithm::Tim => tim_sort_by_key(vec, key),
+            Algorithm::Counting => *vec = counting_sort_by_key(vec, key)?,
+            Algorithm::Radix => lsd_radix_sort_by_key(vec, Radix::Bits8, key),
+        }
+        Ok(())
+    }
+}
+
+fn merge<T: Copy, F: Fn(&T) -> i64>(variant: MergeVariant, vec: &mut [T], key: F) {
+    MergeSort {
+        variant,
+        ..Default::default()
+    }
+    .sort_by_key(vec, key);
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Algorithm::Quick => "quick",
+            Algorithm::Heap => "heap",
+            Algorithm::Insertion => "insertion",
+            Algorithm::Merge => "merge",
+            Algorithm::BottomUpMerge => "bottom-up-merge",
+            Algorithm::NaturalMerge => "natural-merge",
+            Algorithm::Tim => "tim",
+            Algorithm::Counting => "counting",
+            Algorithm::Radix => "radix",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// The error returned when an algorithm name cannot be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseAlgorithmError(String);
+
+impl fmt::Display for ParseAlgorithmError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let names: Vec<String> = Algorithm::ALL.iter().map(|a| a.to_string()).collect();
+        write!(
+            f,
+            "unknown algorithm '{}'; expected one of {}",
+            self.0,
+            names.join(", ")
+        )
+    }
+}
+
+impl std::error::Error for ParseAlgorithmError {}
+
+impl FromStr for Algorithm {
+    type Err = ParseAlgorithmError;
+
+    /// Parse a name written by [`Display`](fmt::Display), ignoring case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Algorithm::ALL
+            .into_iter()
+            .find(|algo| algo.to_string().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| ParseAlgorithmError(s.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::make_random_vec;
+
+    #[test]
+    fn test_every_algorithm_sorts() {
+        let keys = make_random_vec(1_000, 100);
+        let items: Vec<(i64, usize)> = keys
+            .iter()
+            .enumerate()
+            .map(|(i, &k)| (k as i64 - 50, i))
+            .collect();
+        let mut expected = items.clone();
+        expected.sort();
+
+        for algo in Algorithm::ALL {
+            let mut sorted = items.clone();
+            algo.sort_by_key(&mut sorted, |&(k, _)| k).unwrap();
+            let sorted_keys: Vec<i64> = sorted.iter().map(|&(k, _)| k).collect();
+            let expected_keys: Vec<i64> = expected.iter().map(|&(k, _)| k).collect();
+            assert_eq!(sorted_keys, expected_keys, "{algo}");
+            if algo.is_stable() {
+                assert_eq!(sorted, expected, "{algo}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_counting_range_too_large() {
+        let mut vec = vec![0, i64::MAX];
+        assert!(matches!(
+            Algorithm::Counting.sort_by_key(&mut vec, |&x| x),
+            Err(CountingSortError::RangeTooLarge { .. })
+        ));
+        Algorithm::Radix.sort_by_key(&mut vec, |&x| x).unwrap();
+        assert_eq!(vec, vec![0, i64::MAX]);
+    }
+
+    #[test]
+    fn test_parse_round_trip() {
+        for algo in Algorithm::ALL {
+            assert_eq!(algo.to_string().parse(), Ok(algo));
+        }
+        assert_eq!("Heap".parse(), Ok(Algorithm::Heap));
+        assert_eq!("Natural-Merge".parse(), Ok(Algorithm::NaturalMerge));
+        assert!("bogo".parse::<Algorithm>().is_err());
+    }
+}
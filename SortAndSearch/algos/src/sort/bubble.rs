@@ -0,0 +1,319 @@
+use std::fmt;
+
+use super::stats::SortStats;
+
+/// Sort the slice in place by repeatedly swapping adjacent out-of-order items.
+pub fn bubble_sort(vec: &mut [i32]) {
+    for i in 1..vec.len() {
+        for j in 0..(vec.len() - i) {
+            if vec[j] > vec[j + 1] {
+                vec.swap(j + 1, j);
+            }
+        }
+    }
+}
+
+/// The bubble sort variants that count their work.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BubbleVariant {
+    /// Always make n - 1 full passes, like [`bubble_sort`].
+    #[default]
+    Plain,
+    /// Stop after a pass that swaps nothing.
+    EarlyExit,
+    /// End each pass where the previous one made its last swap, since
+    /// everything after it is already in place.
+    LastSwap,
+    /// Alternate forward and backward passes, so small items near the end
+    /// move to the front as fast as large items move to the back.
+    CocktailShaker,
+    /// Compare items a shrinking gap apart, finishing with plain passes once
+    /// the gap reaches 1.
+    Comb,
+}
+
+impl BubbleVariant {
+    /// Every variant.
+    pub const ALL: [BubbleVariant; 5] = [
+        BubbleVariant::Plain,
+        BubbleVariant::EarlyExit,
+        BubbleVariant::LastSwap,
+        BubbleVariant::CocktailShaker,
+        BubbleVariant::Comb,
+    ];
+
+    /// Sort the slice in place and return the work it took.
+    pub fn sort(self, vec: &mut [i32]) -> SortStats {
+        let mut counter = Counter {
+            vec,
+            stats: SortStats::default(),
+        };
+        match self {
+            BubbleVariant::Plain => counter.plain(),
+            BubbleVariant::EarlyExit => counter.early_exit(),
+            BubbleVariant::LastSwap => counter.last_swap(),
+            BubbleVariant::CocktailShaker => counter.cocktail_shaker(),
+            BubbleVariant::Comb => counter.comb(),
+        }
+        counter.stats
+    }
+}
+
+impl fmt::Display for BubbleVariant {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            BubbleVariant::Plain => "plain",
+            BubbleVariant::EarlyExit => "early-exit",
+            BubbleVariant::LastSwap => "last-swap",
+            BubbleVariant::CocktailShaker => "cocktail-shaker",
+            BubbleVariant::Comb => "comb",
+        };
+        // Pad so the names line up in tables.
+        f.pad(name)
+    }
+}
+
+// A slice whose compare-and-swap steps are counted.
+struct Counter<'a> {
+    vec: &'a mut [i32],
+    stats: SortStats,
+}
+
+impl Counter<'_> {
+    // Swap vec[i] and vec[j] if they are out of order, where i < j, and
+    // return true if they were.
+    fn order(&mut self, i: usize, j: usize) -> bool {
+        self.stats.comparisons += 1;
+        if self.vec[i] > self.vec[j] {
+            self.vec.swap(i, j);
+            self.stats.swaps += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn plain(&mut self) {
+        let len = self.vec.len();
+        for i in 1..len {
+            for j in 0..(len - i) {
+                self.order(j, j + 1);
+            }
+        }
+    }
+
+    fn early_exit(&mut self) {
+        let mut end = self.vec.len();
+        let mut swapped = true;
+        while swapped && end > 1 {
+            swapped = false;
+            for j in 0..end - 1 {
+                swapped |= self.order(j, j + 1);
+            }
+            end -= 1;
+        }
+    }
+
+    fn last_swap(&mut self) {
+        let mut end = self.vec.len();
+        while end > 1 {
+            let mut last = 0;
+            for j in 0..end - 1 {
+                if self.order(j, j + 1) {
+                    last = j + 1;
+                }
+            }
+            end = last;
+        }
+    }
+
+    fn cocktail_shaker(&mut self) {
+        // Only vec[start..end] may still be out of order.
+        let mut start = 0;
+        let mut end = self.vec.len();
+        while end - start > 1 {
+            let mut last = start;
+            for j in start..end - 1 {
+                if self.order(j, j + 1) {
+                    last = j + 1;
+                }
+            }
+            end = last;
+            if end - start <= 1 {
+                break;
+            }
+            let mut first = end;
+            for j in (start..end - 1).rev() {
+                if self.order(j, j + 1) {
+                    first = j;
+                }
+            }
+            start = first;
+        }
+    }
+
+    fn comb(&mut self) {
+        let len = self.vec.len();
+        let mut gap = len;
+        let mut swapped = true;
+        while gap > 1 || swapped {
+            // Shrinking by 1.3 each time works well in practice.
+            gap = (gap * 10 / 13).max(1);
+            swapped = false;
+            for j in 0..len.saturating_sub(gap) {
+                swapped |= self.order(j, j + gap);
+            }
+        }
+    }
+}
+
+/// Sort the slice in place like [`bubble_sort`] and return the work it took.
+pub fn bubble_sort_with_stats(vec: &mut [i32]) -> SortStats {
+    BubbleVariant::Plain.sort(vec)
+}
+
+/// Sort the slice in place, stopping after a pass that swaps nothing.
+pub fn early_exit_bubble_sort(vec: &mut [i32]) -> SortStats {
+    BubbleVariant::EarlyExit.sort(vec)
+}
+
+/// Sort the slice in place, ending each pass at the previous pass's last
+/// swap.
+pub fn last_swap_bubble_sort(vec: &mut [i32]) -> SortStats {
+    BubbleVariant::LastSwap.sort(vec)
+}
+
+/// Sort the slice in place with cocktail shaker sort.
+pub fn cocktail_shaker_sort(vec: &mut [i32]) -> SortStats {
+    BubbleVariant::CocktailShaker.sort(vec)
+}
+
+/// Sort the slice in place with comb sort.
+pub fn comb_sort(vec: &mut [i32]) -> SortStats {
+    BubbleVariant::Comb.sort(vec)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::make_random_vec;
+    use crate::{Distribution, Prng};
+
+    #[test]
+    fn test_bubble_sort() {
+        let mut vec = vec![3, 2, 1];
+        bubble_sort(&mut vec);
+        assert_eq!(vec, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_check_identical() {
+        let num_items = 10;
+        let max = 100;
+        let vec = make_random_vec(num_items, max);
+        let mut vec2 = vec.clone();
+        bubble_sort(&mut vec2);
+        let (is_identical, residual) =
+            vec.iter()
+                .fold((true, vec2), |(is_identical, mut residual), &x| {
+                    if is_identical {
+                        if let Some(pos) = residual.iter().position(|&y| y == x) {
+                            residual.remove(pos);
+                            (is_identical, residual)
+                        } else {
+                            (false, residual)
+                        }
+                    } else {
+                        (is_identical, residual)
+                    }
+                });
+        assert!(is_identical && residual.is_empty());
+    }
+
+    // Count the pairs that are out of order, which is the number of swaps any
+    // sort of adjacent items must make.
+    fn inversions(vec: &[i32]) -> u64 {
+        let mut count = 0;
+        for i in 0..vec.len() {
+            for j in i + 1..vec.len() {
+                if vec[i] > vec[j] {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    #[test]
+    fn test_variants_sort() {
+        let mut prng = Prng::with_seed(24);
+        for dist in Distribution::ALL {
+            for num_items in [1, 2, 3, 10, 257] {
+                let vec = dist.generate(&mut prng, num_items, 100);
+                let mut expected = vec.clone();
+                bubble_sort(&mut expected);
+                for variant in BubbleVariant::ALL {
+                    let mut sorted = vec.clone();
+                    let stats = variant.sort(&mut sorted);
+                    assert_eq!(sorted, expected, "{variant} on {dist}");
+                    if variant != BubbleVariant::Comb {
+                        assert_eq!(stats.swaps, inversions(&vec), "{variant} on {dist}");
+                    }
+                }
+            }
+        }
+        assert_eq!(BubbleVariant::Comb.sort(&mut []), SortStats::default());
+    }
+
+    #[test]
+    fn test_comparisons() {
+        let n = 100;
+        let sorted: Vec<i32> = (0..n).collect();
+        let reverse: Vec<i32> = (0..n).rev().collect();
+        let all = (n * (n - 1) / 2) as u64;
+        let count = |variant: BubbleVariant, vec: &[i32]| variant.sort(&mut vec.to_vec());
+
+        assert_eq!(count(BubbleVariant::Plain, &sorted).comparisons, all);
+        assert_eq!(count(BubbleVariant::Plain, &reverse).comparisons, all);
+        for variant in [
+            BubbleVariant::EarlyExit,
+            BubbleVariant::LastSwap,
+            BubbleVariant::CocktailShaker,
+        ] {
+            let stats = count(variant, &sorted);
+            assert_eq!(
+                stats,
+                SortStats {
+                    comparisons: (n - 1) as u64,
+                    ..Default::default()
+                }
+            );
+            assert_eq!(count(variant, &reverse).swaps, all);
+        }
+
+        // One small item at the end takes n - 1 passes to bubble forward, but
+        // a single backward pass of cocktail shaker sort.
+        let mut rotated: Vec<i32> = (1..n).collect();
+        rotated.push(0);
+        assert_eq!(count(BubbleVariant::EarlyExit, &rotated).comparisons, all);
+        assert!(count(BubbleVariant::CocktailShaker, &rotated).comparisons < 3 * n as u64);
+
+        // Comb sort needs far fewer swaps than there are inversions.
+        assert!(count(BubbleVariant::Comb, &reverse).swaps < all / 4);
+    }
+
+    #[test]
+    fn test_bubble_sort_with_stats() {
+        for n in [0u64, 1, 2, 10, 300] {
+            let mut vec = make_random_vec(n as i32, 50);
+            let input = vec.clone();
+            let stats = bubble_sort_with_stats(&mut vec);
+            assert_eq!(stats.comparisons, n * n.saturating_sub(1) / 2);
+            assert_eq!(stats.swaps, inversions(&input));
+            assert_eq!((stats.moves, stats.allocations, stats.max_depth), (0, 0, 0));
+            let mut expected = input;
+            bubble_sort(&mut expected);
+            assert_eq!(vec, expected);
+        }
+    }
+}
@@ -0,0 +1,457 @@
+use core::fmt;
+
+use super::stats::{Counters, Probe, SortStats};
+use crate::prng::{Prng, RandomSource};
+
+/// A customer record, ordered by its number of purchases.
+#[derive(Debug, Default, Clone)]
+pub struct Customer {
+    pub id: String,
+    pub num_purchases: i32,
+}
+
+impl PartialEq for Customer {
+    fn eq(&self, other: &Self) -> bool {
+        self.num_purchases == other.num_purchases
+    }
+}
+
+impl PartialOrd for Customer {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        self.num_purchases.partial_cmp(&other.num_purchases)
+    }
+}
+
+impl fmt::Display for Customer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "({}, {})", self.id, self.num_purchases)
+    }
+}
+
+/// Make `num_items` customers with a random number of purchases in [0, max).
+pub fn make_random_customers(num_items: i32, max: i32) -> Vec<Customer> {
+    make_random_customers_with(&mut Prng::new(), num_items, max)
+}
+
+/// Make `num_items` customers with a random number of purchases in [0, max),
+/// drawn from any random source.
+pub fn make_random_customers_with<R>(prng: &mut R, num_items: i32, max: i32) -> Vec<Customer>
+where
+    R: RandomSource + ?Sized,
+{
+    let mut vec: Vec<Customer> = Vec::with_capacity(num_items as usize);
+
+    for i in 0..num_items {
+        vec.push(Customer {
+            id: format!("C{}", i),
+            num_purchases: prng.next_i32(0, max),
+        });
+    }
+    vec
+}
+
+/// Make one customer per entry of `purchases`, numbered from C0.
+pub fn make_customers(purchases: &[i32]) -> Vec<Customer> {
+    purchases
+        .iter()
+        .enumerate()
+        .map(|(i, &num_purchases)| Customer {
+            id: format!("C{}", i),
+            num_purchases,
+        })
+        .collect()
+}
+
+/// The largest count table the counting sorts allocate by default: 2^24
+/// entries, or 128 MiB on 64-bit targets.
+pub const DEFAULT_MAX_COUNTS: usize = 1 << 24;
+
+/// The reasons a counting sort can refuse its input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CountingSortError {
+    /// A key was below zero.
+    NegativeKey { key: i64 },
+    /// A key was above the maximum the caller promised.
+    KeyAboveMax { key: i64, max: i64 },
+    /// The key range needs more counters than the memory cap allows.
+    RangeTooLarge { range: u64, limit: usize },
+}
+
+impl fmt::Display for CountingSortError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NegativeKey { key } => write!(f, "key {key} is negative"),
+            Self::KeyAboveMax { key, max } => write!(f, "key {key} is above the maximum {max}"),
+            Self::RangeTooLarge { range, limit } => write!(
+                f,
+                "key range of {range} values exceeds the limit of {limit} counters"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CountingSortError {}
+
+/// Return the customers sorted by `num_purchases`, which must lie in [0, max].
+///
+/// The sort is stable: customers with equal purchases keep their order.
+/// Fails instead of allocating more than [`DEFAULT_MAX_COUNTS`] counters.
+pub fn counting_sort(v: &[Customer], max: i32) -> Result<Vec<Customer>, CountingSortError> {
+    counting_sort_with_limit(v, max, DEFAULT_MAX_COUNTS)
+}
+
+/// Like [`counting_sort`], but with a caller-chosen cap on the number of
+/// counters.
+pub fn counting_sort_with_limit(
+    v: &[Customer],
+    max: i32,
+    limit: usize,
+) -> Result<Vec<Customer>, CountingSortError> {
+    counting_sort_probed(v, max, limit, &())
+}
+
+/// Like [`counting_sort`], but also return the work it took. Counting sort
+/// makes no comparisons; it moves every customer once into the output.
+pub fn counting_sort_with_stats(
+    v: &[Customer],
+    max: i32,
+) -> Result<(Vec<Customer>, SortStats), CountingSortError> {
+    let counters = Counters::default();
+    let sorted = counting_sort_probed(v, max, DEFAULT_MAX_COUNTS, &counters)?;
+    Ok((sorted, counters.stats()))
+}
+
+fn counting_sort_probed<P: Probe>(
+    v: &[Customer],
+    max: i32,
+    limit: usize,
+    probe: &P,
+) -> Result<Vec<Customer>, CountingSortError> {
+    for x in v {
+        if x.num_purchases < 0 {
+            return Err(CountingSortError::NegativeKey {
+                key: x.num_purchases as i64,
+            });
+        }
+        if x.num_purchases > max {
+            return Err(CountingSortError::KeyAboveMax {
+                key: x.num_purchases as i64,
+                max: max as i64,
+            });
+        }
+    }
+    if v.is_empty() {
+        return Ok(Vec::new());
+    }
+    // Every key is in [0, max], so max is not negative here.
+    let range = max as u64 + 1;
+    if range > limit as u64 {
+        return Err(CountingSortError::RangeTooLarge { range, limit });
+    }
+
+    let mut counts = vec![0; range as usize];
+    let mut sorted = vec![Customer::default(); v.len()];
+    probe.allocated();
+    probe.allocated();
+
+    // Count the number of occurrences of each value.
+    v.iter().for_each(|x| counts[x.num_purchases as usize] += 1);
+
+    // Modify counts to contain the number of elements <= i.
+    for i in 1..counts.len() {
+        counts[i] += counts[i - 1];
+    }
+
+    // Build the sorted array.
+    for i in (0..v.len()).rev() {
+        let index = counts[v[i].num_purchases as usize] - 1;
+        sorted[index] = v[i].clone();
+        probe.moved(1);
+        counts[v[i].num_purchases as usize] -= 1;
+    }
+    Ok(sorted)
+}
+
+/// Return the items sorted by the integer `key`.
+///
+/// The key range is discovered from the items, so keys may be negative and
+/// need not start at zero. The sort is stable and runs in O(n + k) time,
+/// where k is the distance between the smallest and largest key. Fails
+/// instead of allocating more than [`DEFAULT_MAX_COUNTS`] counters.
+///
+/// The key is an `i64` rather than a `usize` so that negative keys can be
+/// passed as they are; the offset from the smallest key indexes the counts.
+/// Unsigned keys such as status codes or region indices convert losslessly
+/// up to `i64::MAX`.
+pub fn counting_sort_by_key<T, F>(items: &[T], key: F) -> Result<Vec<T>, CountingSortError>
+where
+    T: Clone,
+    F: Fn(&T) -> i64,
+{
+    let keys: Vec<i64> = items.iter().map(&key).collect();
+    let (Some(&min), Some(&max)) = (keys.iter().min(), keys.iter().max()) else {
+        return Ok(Vec::new());
+    };
+    let range = max.abs_diff(min).saturating_add(1);
+    if range > DEFAULT_MAX_COUNTS as u64 {
+        return Err(CountingSortError::RangeTooLarge {
+            range,
+            limit: DEFAULT_MAX_COUNTS,
+        });
+    }
+
+    // Count the number of occurrences of each offset key.
+    let mut counts = vec![0usize; range as usize];
+    keys.iter()
+        .for_each(|&k| counts[k.abs_diff(min) as usize] += 1);
+
+    counts_to_starts(&mut counts);
+
+    // Walk the items in order so that equal keys keep their order.
+    let mut order = vec![0; items.len()];
+    for (i, &k) in keys.iter().enumerate() {
+        let slot = &mut counts[k.abs_diff(min) as usize];
+        order[*slot] = i;
+        *slot += 1;
+    }
+    Ok(order.iter().map(|&i| items[i].clone()).collect())
+}
+
+/// Run one stable counting sort pass from `src` into `dst`.
+///
+/// `digit` must map every item into `[0, counts.len())`. On return,
+/// `counts[d]` holds the end of the run of items with digit `d` in `dst`.
+pub(crate) fn counting_pass<T, F>(src: &[T], dst: &mut [T], counts: &mut [usize], digit: F)
+where
+    T: Copy,
+    F: Fn(&T) -> usize,
+{
+    counts.fill(0);
+    src.iter().for_each(|x| counts[digit(x)] += 1);
+    counts_to_starts(counts);
+    for x in src {
+        let slot = &mut counts[digit(x)];
+        dst[*slot] = *x;
+        *slot += 1;
+    }
+}
+
+// Turn the counts into the first output position of each key.
+fn counts_to_starts(counts: &mut [usize]) {
+    let mut next = 0;
+    for count in counts.iter_mut() {
+        let c = *count;
+        *count = next;
+        next += c;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counting_sort() {
+        let v = vec![
+            Customer {
+                id: "C1".to_string(),
+                num_purchases: 5,
+            },
+            Customer {
+                id: "C2".to_string(),
+                num_purchases: 3,
+            },
+            Customer {
+                id: "C3".to_string(),
+                num_purchases: 1,
+            },
+            Customer {
+                id: "C4".to_string(),
+                num_purchases: 8,
+            },
+            Customer {
+                id: "C5".to_string(),
+                num_purchases: 2,
+            },
+        ];
+        let expected = vec![
+            Customer {
+                id: "C3".to_string(),
+                num_purchases: 1,
+            },
+            Customer {
+                id: "C5".to_string(),
+                num_purchases: 2,
+            },
+            Customer {
+                id: "C2".to_string(),
+                num_purchases: 3,
+            },
+            Customer {
+                id: "C1".to_string(),
+                num_purchases: 5,
+            },
+            Customer {
+                id: "C4".to_string(),
+                num_purchases: 8,
+            },
+        ];
+        let result = counting_sort(&v, 8).unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_make_customers() {
+        let customers = make_customers(&[3, 1]);
+        assert_eq!(customers.len(), 2);
+        assert_eq!(customers[0].to_string(), "(C0, 3)");
+        assert_eq!(customers[1].to_string(), "(C1, 1)");
+    }
+
+    #[test]
+    fn test_same_elements() {
+        let max = 100;
+        let v = make_random_customers(10_000, max);
+        let mut result = counting_sort(&v, max).unwrap();
+        let (identical, res) = v
+            .iter()
+            .fold((true, &mut result), |(is_identical, res), elem| {
+                if is_identical {
+                    if let Some(pos) = res.iter().position(|e| e == elem) {
+                        res.remove(pos);
+                        (is_identical, res)
+                    } else {
+                        (false, res)
+                    }
+                } else {
+                    (is_identical, res)
+                }
+            });
+        assert!(identical && res.is_empty());
+    }
+
+    #[test]
+    fn test_counting_sort_by_key() {
+        // Customers compare by purchases alone, so check the ids as well.
+        let v = make_random_customers(1_000, 50);
+        let result = counting_sort_by_key(&v, |c| c.num_purchases as i64).unwrap();
+        let mut expected = v.clone();
+        // The standard library's sort is stable.
+        expected.sort_by_key(|c| c.num_purchases);
+        let ids = |customers: &[Customer]| -> Vec<String> {
+            customers.iter().map(|c| c.id.clone()).collect()
+        };
+        assert_eq!(ids(&result), ids(&expected));
+        assert_eq!(ids(&result), ids(&counting_sort(&v, 50).unwrap()));
+    }
+
+    #[test]
+    fn test_counting_sort_by_key_negative_keys() {
+        let v = vec![3, -7, 0, -1, 12, -7, 5];
+        let result = counting_sort_by_key(&v, |&x| x).unwrap();
+        assert_eq!(result, vec![-7, -7, -1, 0, 3, 5, 12]);
+    }
+
+    #[test]
+    fn test_counting_sort_by_key_is_stable() {
+        let v = vec![(2, "a"), (-1, "b"), (2, "c"), (0, "d"), (-1, "e")];
+        let result = counting_sort_by_key(&v, |x| x.0).unwrap();
+        assert_eq!(
+            result,
+            vec![(-1, "b"), (-1, "e"), (0, "d"), (2, "a"), (2, "c")]
+        );
+    }
+
+    #[test]
+    fn test_counting_sort_by_key_extremes() {
+        let empty: Vec<i64> = Vec::new();
+        assert!(counting_sort_by_key(&empty, |&x| x).unwrap().is_empty());
+
+        let v = vec![i64::MAX, i64::MAX - 2, i64::MAX - 1];
+        let result = counting_sort_by_key(&v, |&x| x).unwrap();
+        assert_eq!(result, vec![i64::MAX - 2, i64::MAX - 1, i64::MAX]);
+    }
+
+    fn customer(id: &str, num_purchases: i32) -> Customer {
+        Customer {
+            id: id.to_string(),
+            num_purchases,
+        }
+    }
+
+    #[test]
+    fn test_counting_sort_negative_key() {
+        let v = vec![customer("C1", 3), customer("C2", -4)];
+        assert_eq!(
+            counting_sort(&v, 10),
+            Err(CountingSortError::NegativeKey { key: -4 })
+        );
+    }
+
+    #[test]
+    fn test_counting_sort_key_above_max() {
+        let v = vec![customer("C1", 3), customer("C2", 11)];
+        assert_eq!(
+            counting_sort(&v, 10),
+            Err(CountingSortError::KeyAboveMax { key: 11, max: 10 })
+        );
+    }
+
+    #[test]
+    fn test_counting_sort_range_too_large() {
+        let v = vec![customer("C1", 3)];
+        assert_eq!(
+            counting_sort(&v, i32::MAX),
+            Err(CountingSortError::RangeTooLarge {
+                range: 1 << 31,
+                limit: DEFAULT_MAX_COUNTS
+            })
+        );
+        assert!(counting_sort_with_limit(&v, 100, 101).is_ok());
+        assert!(counting_sort_with_limit(&v, 100, 100).is_err());
+    }
+
+    #[test]
+    fn test_counting_sort_empty() {
+        assert_eq!(counting_sort(&[], -1), Ok(Vec::new()));
+        assert_eq!(counting_sort(&[], i32::MAX), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn test_counting_sort_by_key_range_too_large() {
+        let v = vec![i64::MIN, i64::MAX];
+        assert_eq!(
+            counting_sort_by_key(&v, |&x| x),
+            Err(CountingSortError::RangeTooLarge {
+                range: u64::MAX,
+                limit: DEFAULT_MAX_COUNTS
+            })
+        );
+    }
+
+    #[test]
+    fn test_counting_sort_error_display() {
+        let err = CountingSortError::KeyAboveMax { key: 11, max: 10 };
+        assert_eq!(err.to_string(), "key 11 is above the maximum 10");
+    }
+
+    #[test]
+    fn test_counting_sort_with_stats() {
+        let v = make_customers(&[5, 3, 9, 3, 0, 7]);
+        let (sorted, stats) = counting_sort_with_stats(&v, 9).unwrap();
+        assert_eq!(sorted, counting_sort(&v, 9).unwrap());
+        assert_eq!(
+            stats,
+            SortStats {
+                moves: 6,
+                allocations: 2,
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            counting_sort_with_stats(&[], 9).unwrap().1,
+            SortStats::default()
+        );
+        assert!(counting_sort_with_stats(&v, 4).is_err());
+    }
+}
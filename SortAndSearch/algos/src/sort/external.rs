@@ -0,0 +1,525 @@
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use super::quick::quick_sort;
+use crate::heap::PriorityQueue;
+
+/// How the integers are stored in the input and output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    /// One decimal integer per line. Blank lines are skipped.
+    #[default]
+    Text,
+    /// Consecutive 4-byte little-endian integers.
+    Binary,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "text" => Ok(Format::Text),
+            "binary" => Ok(Format::Binary),
+            _ => Err(format!("unknown format '{s}'; expected text or binary")),
+        }
+    }
+}
+
+/// The ways an external sort can fail.
+#[derive(Debug)]
+pub enum ExternalSortError {
+    /// Reading the input, writing the output or using a run file failed.
+    Io(io::Error),
+    /// A line of text input is not an i32. Lines count from 1.
+    InvalidNumber { line: usize, text: String },
+    /// Binary input ended partway through an integer.
+    TruncatedInput,
+}
+
+impl fmt::Display for ExternalSortError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "{err}"),
+            Self::InvalidNumber { line, text } => {
+                write!(f, "line {line}: '{text}' is not a 32-bit integer")
+            }
+            Self::TruncatedInput => write!(f, "binary input ends partway through an integer"),
+        }
+    }
+}
+
+impl std::error::Error for ExternalSortError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ExternalSortError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+/// What an external sort did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExternalSortStats {
+    /// The number of integers sorted.
+    pub items: u64,
+    /// The number of sorted runs spilled to disk; zero if the input fit in
+    /// one chunk.
+    pub runs: usize,
+}
+
+/// Sorts integer files that may not fit in memory.
+///
+/// The input is read in chunks of at most `memory_budget` bytes. Each chunk
+/// is sorted with [`quick_sort`] and spilled to a temporary run file, and the
+/// runs are then merged through a heap into the output. At most `fan_in`
+/// runs are open at once; more runs are first merged in groups into longer
+/// runs. Run files are removed when the sort finishes, even if it fails.
+#[derive(Debug, Clone)]
+pub struct ExternalSort {
+    /// The most bytes of integers to hold in memory at once.
+    pub memory_budget: usize,
+    /// Where to put the run files.
+    pub temp_dir: PathBuf,
+    pub format: Format,
+    /// The most runs to merge in one pass, which bounds the number of open
+    /// files. Values below 2 are treated as 2.
+    pub fan_in: usize,
+}
+
+impl Default for ExternalSort {
+    fn default() -> Self {
+        Self {
+            memory_budget: 64 << 20,
+            temp_dir: std::env::temp_dir(),
+            format: Format::Text,
+            fan_in: 64,
+        }
+    }
+}
+
+// The smallest read buffer given to each run during the merge.
+const MIN_RUN_BUFFER: usize = 64;
+
+// Numbers the run files so that concurrent sorts do not collide.
+static NEXT_RUN: AtomicUsize = AtomicUsize::new(0);
+
+impl ExternalSort {
+    /// Sort the integers from `input` into `output`, both in `self.format`.
+    pub fn sort<R, W>(&self, input: R, output: W) -> Result<ExternalSortStats, ExternalSortError>
+    where
+        R: BufRead,
+        W: Write,
+    {
+        let chunk_len = (self.memory_budget / size_of::<i32>()).max(1);
+        let mut reader = IntReader::new(input, self.format);
+        let mut writer = IntWriter::new(output, self.format);
+        let mut runs: Vec<RunFile> = Vec::new();
+        let mut items = 0;
+
+        let mut chunk = Vec::with_capacity(chunk_len.min(1 << 20));
+        loop {
+            chunk.clear();
+            while chunk.len() < chunk_len {
+                match reader.next()? {
+                    Some(x) => chunk.push(x),
+                    None => break,
+                }
+            }
+            items += chunk.len() as u64;
+            let at_end = chunk.len() < chunk_len || reader.at_end()?;
+            quick_sort(&mut chunk);
+
+            if at_end && runs.is_empty() {
+                // Everything fit in memory, so skip the disk.
+                for &x in &chunk {
+                    writer.write(x)?;
+                }
+                writer.finish()?;
+                return Ok(ExternalSortStats { items, runs: 0 });
+            }
+            if !chunk.is_empty() {
+                runs.push(RunFile::write(&self.temp_dir, &chunk)?);
+            }
+            if at_end {
+                break;
+            }
+        }
+        drop(chunk);
+        let spilled = runs.len();
+
+        // Merge groups of runs into longer runs until one pass can finish.
+        let fan_in = self.fan_in.max(2);
+        while runs.len() > fan_in {
+            let mut merged = Vec::with_capacity(runs.len().div_ceil(fan_in));
+            for group in runs.chunks(fan_in) {
+                let (run, mut out) = RunFile::create(&self.temp_dir)?;
+                self.merge(group, |x| out.write_all(&x.to_le_bytes()))?;
+                out.flush()?;
+                merged.push(run);
+            }
+            // Dropping the old runs deletes them.
+            runs = merged;
+        }
+        self.merge(&runs, |x| writer.write(x))?;
+        writer.finish()?;
+        Ok(ExternalSortStats {
+            items,
+            runs: spilled,
+        })
+    }
+
+    // Merge the sorted runs with a min-heap holding the next value of each,
+    // passing the values to emit in order.
+    fn merge<F>(&self, runs: &[RunFile], mut emit: F) -> io::Result<()>
+    where
+        F: FnMut(i32) -> io::Result<()>,
+    {
+        let buffer = (self.memory_budget / runs.len().max(1)).max(MIN_RUN_BUFFER);
+        let mut readers = Vec::with_capacity(runs.len());
+        for run in runs {
+            let file = File::open(&run.path)?;
+            readers.push(BufReader::with_capacity(buffer, file));
+        }
+
+        let mut heap = PriorityQueue::with_capacity(readers.len());
+        for (i, reader) in readers.iter_mut().enumerate() {
+            if let Some(x) = read_binary(reader)? {
+                heap.push((x, i));
+            }
+        }
+        while let Some((x, i)) = heap.pop() {
+            emit(x)?;
+            if let Some(next) = read_binary(&mut readers[i])? {
+                heap.push((next, i));
+            }
+        }
+        Ok(())
+    }
+}
+
+// A sorted run on disk, deleted when dropped.
+struct RunFile {
+    path: PathBuf,
+}
+
+impl RunFile {
+    // Create a new, empty run file and return a writer for it. An existing
+    // file is never overwritten.
+    fn create(dir: &Path) -> io::Result<(Self, BufWriter<File>)> {
+        let n = NEXT_RUN.fetch_add(1, Ordering::Relaxed);
+        let path = dir.join(format!("external-sort-{}-{n}.run", process::id()));
+        let file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)?;
+        Ok((RunFile { path }, BufWriter::new(file)))
+    }
+
+    fn write(dir: &Path, values: &[i32]) -> io::Result<Self> {
+        let (run, mut out) = Self::create(dir)?;
+        for x in values {
+            out.write_all(&x.to_le_bytes())?;
+        }
+        out.flush()?;
+        Ok(run)
+    }
+}
+
+impl Drop for RunFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+// Read one little-endian i32, or None at a clean end of input.
+fn read_binary<R: Read>(reader: &mut R) -> io::Result<Option<i32>> {
+    let mut bytes = [0; 4];
+    let mut filled = 0;
+    while filled < bytes.len() {
+        match reader.read(&mut bytes[filled..]) {
+            Ok(0) if filled == 0 => return Ok(None),
+            Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+            Ok(n) => filled += n,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(Some(i32::from_le_bytes(bytes)))
+}
+
+// Reads integers one at a time in either format.
+struct IntReader<R> {
+    input: R,
+    format: Format,
+    line: usize,
+    text: String,
+}
+
+impl<R: BufRead> IntReader<R> {
+    fn new(input: R, format: Format) -> Self {
+        Self {
+            input,
+            format,
+            line: 0,
+            text: String::new(),
+        }
+    }
+
+    fn next(&mut self) -> Result<Option<i32>, ExternalSortError> {
+        match self.format {
+            Format::Text => loop {
+                self.text.clear();
+                if self.input.read_line(&mut self.text)? == 0 {
+                    return Ok(None);
+                }
+                self.line += 1;
+                let text = self.text.trim();
+                if text.is_empty() {
+                    continue;
+                }
+                return match text.parse() {
+                    Ok(x) => Ok(Some(x)),
+                    Err(_) => Err(ExternalSortError::InvalidNumber {
+                        line: self.line,
+                        text: text.to_string(),
+                    }),
+                };
+            },
+            Format::Binary => match read_binary(&mut self.input) {
+                Ok(x) => Ok(x),
+                Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => {
+                    Err(ExternalSortError::TruncatedInput)
+                }
+                Err(err) => Err(err.into()),
+            },
+        }
+    }
+
+    // Return true if there is no more input. Trailing blank lines of text
+    // still count as input, which at worst costs one empty check later.
+    fn at_end(&mut self) -> io::Result<bool> {
+        Ok(self.input.fill_buf()?.is_empty())
+    }
+}
+
+// Writes integers one at a time in either format.
+struct IntWriter<W: Write> {
+    output: BufWriter<W>,
+    format: Format,
+}
+
+impl<W: Write> IntWriter<W> {
+    fn new(output: W, format: Format) -> Self {
+        Self {
+            output: BufWriter::new(output),
+            format,
+        }
+    }
+
+    fn write(&mut self, x: i32) -> io::Result<()> {
+        match self.format {
+            Format::Text => writeln!(self.output, "{x}"),
+            Format::Binary => self.output.write_all(&x.to_le_bytes()),
+        }
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.output.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::make_random_vec;
+
+    // Make an empty directory for one test's run files.
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("external-sort-test-{}-{name}", process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn to_text(values: &[i32]) -> String {
+        values.iter().map(|x| format!("{x}\n")).collect()
+    }
+
+    #[test]
+    fn test_file_bigger_than_budget() {
+        let dir = temp_dir("text");
+        let values: Vec<i32> = make_random_vec(50_000, i32::MAX)
+            .iter()
+            .map(|&x| x.wrapping_mul(2))
+            .collect();
+        let input_path = dir.join("input.txt");
+        fs::write(&input_path, to_text(&values)).unwrap();
+        let budget = 16 << 10;
+        assert!(fs::metadata(&input_path).unwrap().len() > budget as u64 * 10);
+
+        let sorter = ExternalSort {
+            memory_budget: budget,
+            temp_dir: dir.clone(),
+            format: Format::Text,
+            ..Default::default()
+        };
+        let input = BufReader::new(File::open(&input_path).unwrap());
+        let mut output = Vec::new();
+        let stats = sorter.sort(input, &mut output).unwrap();
+
+        let mut expected = values.clone();
+        expected.sort();
+        assert_eq!(String::from_utf8(output).unwrap(), to_text(&expected));
+        assert_eq!(stats.items, 50_000);
+        assert_eq!(stats.runs, 50_000usize.div_ceil(budget / 4));
+
+        // Only the input is left; the runs were removed.
+        let left: Vec<_> = fs::read_dir(&dir).unwrap().collect();
+        assert_eq!(left.len(), 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_binary_format() {
+        let dir = temp_dir("binary");
+        let values = make_random_vec(10_000, 1_000);
+        let input: Vec<u8> = values.iter().flat_map(|x| x.to_le_bytes()).collect();
+        let sorter = ExternalSort {
+            memory_budget: 4_000,
+            temp_dir: dir.clone(),
+            format: Format::Binary,
+            ..Default::default()
+        };
+        let mut output = Vec::new();
+        let stats = sorter.sort(input.as_slice(), &mut output).unwrap();
+        assert_eq!(stats.runs, 10);
+
+        let mut expected = values.clone();
+        expected.sort();
+        let expected: Vec<u8> = expected.iter().flat_map(|x| x.to_le_bytes()).collect();
+        assert_eq!(output, expected);
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_fits_in_memory() {
+        let sorter = ExternalSort::default();
+        let mut output = Vec::new();
+        let stats = sorter.sort("3\n\n-1\n2\n".as_bytes(), &mut output).unwrap();
+        assert_eq!(output, b"-1\n2\n3\n");
+        assert_eq!(stats, ExternalSortStats { items: 3, runs: 0 });
+
+        let mut output = Vec::new();
+        let stats = sorter.sort("".as_bytes(), &mut output).unwrap();
+        assert!(output.is_empty());
+        assert_eq!(stats.items, 0);
+    }
+
+    #[test]
+    fn test_exact_multiple_of_budget() {
+        // Two full chunks and nothing left over.
+        let dir = temp_dir("exact");
+        let sorter = ExternalSort {
+            memory_budget: 8,
+            temp_dir: dir.clone(),
+            format: Format::Text,
+            ..Default::default()
+        };
+        let mut output = Vec::new();
+        let stats = sorter.sort("4\n3\n2\n1\n".as_bytes(), &mut output).unwrap();
+        assert_eq!(output, b"1\n2\n3\n4\n");
+        assert_eq!(stats.runs, 2);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_errors_remove_runs() {
+        let dir = temp_dir("errors");
+        let sorter = ExternalSort {
+            memory_budget: 8,
+            temp_dir: dir.clone(),
+            format: Format::Text,
+            ..Default::default()
+        };
+        let err = sorter
+            .sort("1\n2\n3\n4\n5\nbig\n".as_bytes(), io::sink())
+            .unwrap_err();
+        assert_eq!(err.to_string(), "line 6: 'big' is not a 32-bit integer");
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+
+        let binary = ExternalSort {
+            format: Format::Binary,
+            ..sorter
+        };
+        let err = binary.sort(&[1u8, 0, 0, 0, 7][..], io::sink()).unwrap_err();
+        assert!(matches!(err, ExternalSortError::TruncatedInput));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_merge_in_passes() {
+        // 100 runs merged at most 3 at a time take several passes.
+        let dir = temp_dir("passes");
+        let values = make_random_vec(1_000, 1_000_000);
+        let sorter = ExternalSort {
+            memory_budget: 40,
+            temp_dir: dir.clone(),
+            format: Format::Text,
+            fan_in: 3,
+        };
+        let mut output = Vec::new();
+        let stats = sorter
+            .sort(to_text(&values).as_bytes(), &mut output)
+            .unwrap();
+        assert_eq!(stats.runs, 100);
+
+        let mut expected = values.clone();
+        expected.sort();
+        assert_eq!(String::from_utf8(output).unwrap(), to_text(&expected));
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_run_file_is_never_overwritten() {
+        let dir = temp_dir("create-new");
+        let next = NEXT_RUN.load(Ordering::Relaxed);
+        // Claim the next few names, as a leftover from another process might.
+        for n in next..next + 8 {
+            let path = dir.join(format!("external-sort-{}-{n}.run", process::id()));
+            fs::write(path, b"keep").unwrap();
+        }
+        for _ in 0..8 {
+            match RunFile::write(&dir, &[1, 2, 3]) {
+                Ok(run) => drop(run),
+                Err(err) => assert_eq!(err.kind(), io::ErrorKind::AlreadyExists),
+            }
+        }
+        // Other tests may take some of the numbers first, but none of the
+        // claimed files may have been truncated.
+        for entry in fs::read_dir(&dir).unwrap() {
+            assert_eq!(fs::read(entry.unwrap().path()).unwrap(), b"keep");
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_parse_format() {
+        assert_eq!("Binary".parse(), Ok(Format::Binary));
+        assert_eq!("text".parse(), Ok(Format::Text));
+        assert!("csv".parse::<Format>().is_err());
+    }
+}
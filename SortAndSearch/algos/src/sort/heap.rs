@@ -0,0 +1,137 @@
+use std::cmp::Ordering;
+
+use super::stats::Probe;
+use crate::heap::sift_down_by;
+
+/// Sort the slice in place with heapsort, using no memory beyond the slice.
+pub fn heap_sort<T: Ord>(vec: &mut [T]) {
+    heap_sort_by(vec, T::cmp);
+}
+
+/// Sort the slice in place with heapsort, ordering items with `cmp`.
+pub fn heap_sort_by<T, F>(vec: &mut [T], cmp: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    heap_sort_probed(vec, cmp, &());
+}
+
+// Heapsort that reports its swaps to probe.
+pub(crate) fn heap_sort_probed<T, F, P>(vec: &mut [T], mut cmp: F, probe: &P)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    P: Probe,
+{
+    // Sifting swaps once per level the item moves down.
+    let sift = |vec: &mut [T], root: usize, cmp: &mut F| {
+        let end = sift_down_by(vec, root, cmp);
+        for _ in (root + 1).ilog2()..(end + 1).ilog2() {
+            probe.swapped();
+        }
+    };
+
+    // Build a max-heap bottom-up.
+    for root in (0..vec.len() / 2).rev() {
+        sift(vec, root, &mut cmp);
+    }
+
+    // Repeatedly move the largest item behind the shrinking heap.
+    for end in (1..vec.len()).rev() {
+        vec.swap(0, end);
+        probe.swapped();
+        sift(&mut vec[..end], 0, &mut cmp);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sort::stats::Counters;
+    use crate::util::make_random_vec;
+
+    #[test]
+    fn test_heap_sort() {
+        let mut vec = vec![3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5];
+        heap_sort(&mut vec);
+        assert_eq!(vec, vec![1, 1, 2, 3, 3, 4, 5, 5, 5, 6, 9]);
+    }
+
+    #[test]
+    fn test_heap_sort_random_vec() {
+        let mut vec = make_random_vec(1_000, 100);
+        let mut expected = vec.clone();
+        expected.sort();
+        heap_sort(&mut vec);
+        assert_eq!(vec, expected);
+    }
+
+    #[test]
+    fn test_vectors_are_identical() {
+        let mut vec = make_random_vec(1_000, 100);
+        let vec2 = vec.clone();
+        heap_sort(&mut vec);
+        let (identical, res) = vec.iter().fold((true, vec2), |(identical, mut vec2), x| {
+            if let Some(pos) = vec2.iter().position(|&y| y == *x) {
+                vec2.remove(pos);
+                (identical, vec2)
+            } else {
+                (false, vec2)
+            }
+        });
+        assert!(identical && res.is_empty());
+    }
+
+    #[test]
+    fn test_heap_sort_by_descending() {
+        let mut vec = make_random_vec(500, 1_000);
+        let mut expected = vec.clone();
+        expected.sort_by(|a, b| b.cmp(a));
+        heap_sort_by(&mut vec, |a, b| b.cmp(a));
+        assert_eq!(vec, expected);
+        heap_sort::<i32>(&mut []);
+        let mut one = [7];
+        heap_sort(&mut one);
+        assert_eq!(one, [7]);
+    }
+
+    #[test]
+    fn test_heap_sort_counts_swaps() {
+        // A heapsort that swaps through a counting helper.
+        fn reference(vec: &mut [i32]) -> u64 {
+            let mut swaps = 0;
+            let mut sift = |vec: &mut [i32], mut root: usize| loop {
+                let mut child = 2 * root + 1;
+                if child >= vec.len() {
+                    break;
+                }
+                if child + 1 < vec.len() && vec[child] < vec[child + 1] {
+                    child += 1;
+                }
+                if vec[root] >= vec[child] {
+                    break;
+                }
+                vec.swap(root, child);
+                swaps += 1;
+                root = child;
+            };
+            for root in (0..vec.len() / 2).rev() {
+                sift(vec, root);
+            }
+            for end in (1..vec.len()).rev() {
+                vec.swap(0, end);
+                sift(&mut vec[..end], 0);
+            }
+            swaps + vec.len().saturating_sub(1) as u64
+        }
+
+        for n in [0, 1, 2, 7, 1_000] {
+            let mut vec = make_random_vec(n, 100);
+            let mut expected = vec.clone();
+            let swaps = reference(&mut expected);
+            let counters = Counters::default();
+            heap_sort_probed(&mut vec, i32::cmp, &counters);
+            assert_eq!(vec, expected);
+            assert_eq!(counters.stats().swaps, swaps, "{n}");
+        }
+    }
+}
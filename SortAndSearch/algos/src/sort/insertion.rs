@@ -0,0 +1,64 @@
+use std::cmp::Ordering;
+
+use super::stats::Probe;
+
+/// Sort the slice in place with insertion sort.
+///
+/// Quadratic in general, but fast on short or nearly sorted slices.
+pub fn insertion_sort<T: Ord>(vec: &mut [T]) {
+    insertion_sort_by(vec, T::cmp);
+}
+
+/// Sort the slice in place with insertion sort, ordering items with `cmp`.
+pub fn insertion_sort_by<T, F>(vec: &mut [T], cmp: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    insertion_sort_probed(vec, cmp, &());
+}
+
+// Insertion sort that reports its swaps to probe.
+pub(crate) fn insertion_sort_probed<T, F, P>(vec: &mut [T], mut cmp: F, probe: &P)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    P: Probe,
+{
+    for i in 1..vec.len() {
+        // Move item i left until its predecessor is no greater.
+        let mut j = i;
+        while j > 0 && cmp(&vec[j - 1], &vec[j]) == Ordering::Greater {
+            vec.swap(j - 1, j);
+            probe.swapped();
+            j -= 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::make_random_vec;
+
+    #[test]
+    fn test_insertion_sort() {
+        let mut vec = vec![3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5];
+        insertion_sort(&mut vec);
+        assert_eq!(vec, vec![1, 1, 2, 3, 3, 4, 5, 5, 5, 6, 9]);
+    }
+
+    #[test]
+    fn test_insertion_sort_is_stable() {
+        let mut vec = vec![(2, 'a'), (1, 'b'), (2, 'c'), (1, 'd')];
+        insertion_sort_by(&mut vec, |a, b| a.0.cmp(&b.0));
+        assert_eq!(vec, vec![(1, 'b'), (1, 'd'), (2, 'a'), (2, 'c')]);
+    }
+
+    #[test]
+    fn test_insertion_sort_random_vec() {
+        let mut vec = make_random_vec(500, 100);
+        let mut expected = vec.clone();
+        expected.sort();
+        insertion_sort(&mut vec);
+        assert_eq!(vec, expected);
+    }
+}
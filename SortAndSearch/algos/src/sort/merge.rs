@@ -0,0 +1,378 @@
+use std::cmp::Ordering;
+use std::mem;
+
+use super::insertion::insertion_sort_by;
+
+/// The order in which [`MergeSort`] merges runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MergeVariant {
+    /// Split in half recursively and merge on the way back up.
+    #[default]
+    TopDown,
+    /// Merge runs of width 1, 2, 4, ... in passes over the slice, without
+    /// recursion.
+    BottomUp,
+    /// Find the runs already in the input and merge neighbouring runs until
+    /// one is left. Sorted input takes a single pass.
+    Natural,
+}
+
+/// A configurable merge sort.
+///
+/// Every variant is stable and O(n log n) in the worst case. The items are
+/// cloned once into a scratch buffer, which all merges share; after that
+/// items only move by swapping between the slice and the buffer.
+///
+/// ```
+/// use algos::sort::merge::{MergeSort, MergeVariant};
+///
+/// let mut vec = vec![3, 1, 2];
+/// MergeSort {
+///     variant: MergeVariant::Natural,
+///     ..Default::default()
+/// }
+/// .sort(&mut vec);
+/// assert_eq!(vec, vec![1, 2, 3]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MergeSort {
+    pub variant: MergeVariant,
+    /// Top-down and bottom-up merge sort finish runs of at most this many
+    /// items with insertion sort. Natural merge sort ignores it.
+    pub insertion_threshold: usize,
+}
+
+impl Default for MergeSort {
+    fn default() -> Self {
+        Self {
+            variant: MergeVariant::default(),
+            insertion_threshold: 16,
+        }
+    }
+}
+
+impl MergeSort {
+    /// Sort the slice in place.
+    pub fn sort<T: Ord + Clone>(&self, vec: &mut [T]) {
+        self.sort_by(vec, T::cmp);
+    }
+
+    /// Sort the slice in place, ordering items with `cmp`.
+    pub fn sort_by<T, F>(&self, vec: &mut [T], mut cmp: F)
+    where
+        T: Clone,
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        if vec.len() < 2 {
+            return;
+        }
+        let mut scratch = vec.to_vec();
+        let threshold = self.insertion_threshold.max(1);
+        match self.variant {
+            MergeVariant::TopDown => split_merge(&mut scratch, vec, threshold, &mut cmp),
+            MergeVariant::BottomUp => bottom_up(vec, &mut scratch, threshold, &mut cmp),
+            MergeVariant::Natural => natural(vec, &mut scratch, &mut cmp),
+        }
+    }
+
+    /// Sort the slice in place, ordering items by `key`.
+    pub fn sort_by_key<T, K, F>(&self, vec: &mut [T], mut key: F)
+    where
+        T: Clone,
+        K: Ord,
+        F: FnMut(&T) -> K,
+    {
+        self.sort_by(vec, |a, b| key(a).cmp(&key(b)));
+    }
+}
+
+// Sort the items of src into dst, where both start out holding the same
+// items. src is left in an unspecified order.
+fn split_merge<T, F>(src: &mut [T], dst: &mut [T], threshold: usize, cmp: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let len = dst.len();
+    if len <= threshold {
+        insertion_sort_by(dst, &mut *cmp);
+        return;
+    }
+    let mid = len / 2;
+    // Sort each half of dst into src, then merge the halves back into dst.
+    split_merge(&mut dst[..mid], &mut src[..mid], threshold, cmp);
+    split_merge(&mut dst[mid..], &mut src[mid..], threshold, cmp);
+    merge(src, dst, mid, cmp);
+}
+
+// Merge runs of doubling width, moving between vec and scratch each pass.
+fn bottom_up<T, F>(vec: &mut [T], scratch: &mut [T], threshold: usize, cmp: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    for chunk in vec.chunks_mut(threshold) {
+        insertion_sort_by(chunk, &mut *cmp);
+    }
+    let len = vec.len();
+    let mut bounds: Vec<usize> = (0..len).step_by(threshold).chain([len]).collect();
+    merge_passes(vec, scratch, &mut bounds, cmp);
+}
+
+// Merge the runs already present in vec.
+fn natural<T, F>(vec: &mut [T], scratch: &mut [T], cmp: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mut bounds = vec![0];
+    let mut start = 0;
+    while start < vec.len() {
+        let end = find_run(&mut vec[start..], cmp) + start;
+        bounds.push(end);
+        start = end;
+    }
+    merge_passes(vec, scratch, &mut bounds, cmp);
+}
+
+/// Return the length of the run at the start of the slice. A strictly
+/// descending run is reversed in place, so the run is ascending on return.
+///
+/// Only strictly descending runs are reversed, which keeps equal items in
+/// their original order.
+pub fn find_run<T, F>(vec: &mut [T], cmp: &mut F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    if vec.len() < 2 {
+        return vec.len();
+    }
+    let mut end = 2;
+    if cmp(&vec[1], &vec[0]) == Ordering::Less {
+        while end < vec.len() && cmp(&vec[end], &vec[end - 1]) == Ordering::Less {
+            end += 1;
+        }
+        vec[..end].reverse();
+    } else {
+        while end < vec.len() && cmp(&vec[end], &vec[end - 1]) != Ordering::Less {
+            end += 1;
+        }
+    }
+    end
+}
+
+// Merge neighbouring runs until one is left. Run i spans
+// bounds[i]..bounds[i + 1]. Each pass moves every item between vec and
+// scratch, and the sorted items are swapped back into vec if they end up in
+// scratch.
+fn merge_passes<T, F>(vec: &mut [T], scratch: &mut [T], bounds: &mut Vec<usize>, cmp: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mut in_scratch = false;
+    while bounds.len() > 2 {
+        let (src, dst) = if in_scratch {
+            (&mut *scratch, &mut *vec)
+        } else {
+            (&mut *vec, &mut *scratch)
+        };
+        let mut merged = vec![0];
+        for pair in bounds[..bounds.len() - 1].chunks(2) {
+            let lo = pair[0];
+            let end = *bounds
+                .get(merged.len() * 2)
+                .unwrap_or(&bounds[bounds.len() - 1]);
+            if pair.len() == 2 {
+                merge(&mut src[lo..end], &mut dst[lo..end], pair[1] - lo, cmp);
+            } else {
+                // An odd run out has no partner this pass.
+                src[lo..end].swap_with_slice(&mut dst[lo..end]);
+            }
+            merged.push(end);
+        }
+        *bounds = merged;
+        in_scratch = !in_scratch;
+    }
+    if in_scratch {
+        vec.swap_with_slice(scratch);
+    }
+}
+
+// Merge the sorted runs src[..mid] and src[mid..] into dst, taking from the
+// left run on ties so that the sort is stable. Items are swapped rather than
+// cloned, so src ends up holding the old contents of dst.
+fn merge<T, F>(src: &mut [T], dst: &mut [T], mid: usize, cmp: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let (mut i, mut j) = (0, mid);
+    for slot in dst.iter_mut() {
+        let take_left = j == src.len() || (i < mid && cmp(&src[j], &src[i]) != Ordering::Less);
+        let from = if take_left { &mut i } else { &mut j };
+        mem::swap(slot, &mut src[*from]);
+        *from += 1;
+    }
+}
+
+/// Sort the slice in place with top-down merge sort.
+pub fn merge_sort<T: Ord + Clone>(vec: &mut [T]) {
+    MergeSort::default().sort(vec);
+}
+
+/// Sort the slice in place with top-down merge sort, ordering items with
+/// `cmp`.
+pub fn merge_sort_by<T, F>(vec: &mut [T], cmp: F)
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    MergeSort::default().sort_by(vec, cmp);
+}
+
+/// Sort the slice in place with bottom-up merge sort.
+pub fn bottom_up_merge_sort<T: Ord + Clone>(vec: &mut [T]) {
+    MergeSort {
+        variant: MergeVariant::BottomUp,
+        ..Default::default()
+    }
+    .sort(vec);
+}
+
+/// Sort the slice in place with natural merge sort.
+pub fn natural_merge_sort<T: Ord + Clone>(vec: &mut [T]) {
+    MergeSort {
+        variant: MergeVariant::Natural,
+        ..Default::default()
+    }
+    .sort(vec);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::make_random_vec;
+    use crate::Distribution;
+    use crate::Prng;
+
+    const VARIANTS: [MergeVariant; 3] = [
+        MergeVariant::TopDown,
+        MergeVariant::BottomUp,
+        MergeVariant::Natural,
+    ];
+
+    fn sorters() -> Vec<MergeSort> {
+        let mut sorters = Vec::new();
+        for variant in VARIANTS {
+            for insertion_threshold in [0, 1, 2, 16] {
+                sorters.push(MergeSort {
+                    variant,
+                    insertion_threshold,
+                });
+            }
+        }
+        sorters
+    }
+
+    #[test]
+    fn test_merge_sort_random() {
+        let vec = make_random_vec(1_000, 100);
+        let mut expected = vec.clone();
+        expected.sort();
+        for sorter in sorters() {
+            let mut sorted = vec.clone();
+            sorter.sort(&mut sorted);
+            assert_eq!(sorted, expected, "{sorter:?}");
+        }
+    }
+
+    #[test]
+    fn test_merge_sort_small() {
+        for sorter in sorters() {
+            for len in 0..40 {
+                let mut vec: Vec<i32> = (0..len).rev().collect();
+                sorter.sort(&mut vec);
+                assert_eq!(vec, (0..len).collect::<Vec<i32>>(), "{sorter:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_merge_sort_distributions() {
+        let mut prng = Prng::with_seed(21);
+        for dist in Distribution::ALL {
+            let vec = dist.generate(&mut prng, 2_000, 500);
+            let mut expected = vec.clone();
+            expected.sort();
+            for sorter in sorters() {
+                let mut sorted = vec.clone();
+                sorter.sort(&mut sorted);
+                assert_eq!(sorted, expected, "{sorter:?} on {dist}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_merge_sort_is_stable() {
+        let keys = make_random_vec(1_000, 10);
+        let vec: Vec<(i32, usize)> = keys.into_iter().zip(0..).collect();
+        let mut expected = vec.clone();
+        // The standard library's sort is stable.
+        expected.sort_by_key(|&(k, _)| k);
+        for sorter in sorters() {
+            let mut sorted = vec.clone();
+            sorter.sort_by_key(&mut sorted, |&(k, _)| k);
+            assert_eq!(sorted, expected, "{sorter:?}");
+        }
+    }
+
+    #[test]
+    fn test_merge_sort_strings() {
+        let mut vec: Vec<String> = ["pear", "apple", "fig", "apple", "kiwi"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        merge_sort(&mut vec);
+        assert_eq!(vec, ["apple", "apple", "fig", "kiwi", "pear"]);
+
+        merge_sort_by(&mut vec, |a, b| b.len().cmp(&a.len()));
+        assert_eq!(vec, ["apple", "apple", "kiwi", "pear", "fig"]);
+    }
+
+    #[test]
+    fn test_free_functions() {
+        let vec = make_random_vec(500, 1_000);
+        let mut expected = vec.clone();
+        expected.sort();
+        for sort in [merge_sort, bottom_up_merge_sort, natural_merge_sort] {
+            let mut sorted = vec.clone();
+            sort(&mut sorted);
+            assert_eq!(sorted, expected);
+        }
+    }
+
+    #[test]
+    fn test_find_run() {
+        let mut cmp = i32::cmp;
+        let mut vec = vec![1, 2, 2, 5, 3];
+        assert_eq!(find_run(&mut vec, &mut cmp), 4);
+
+        let mut vec = vec![5, 4, 1, 1];
+        assert_eq!(find_run(&mut vec, &mut cmp), 3);
+        assert_eq!(vec, vec![1, 4, 5, 1]);
+
+        assert_eq!(find_run(&mut [7], &mut cmp), 1);
+        assert_eq!(find_run::<i32, _>(&mut [], &mut cmp), 0);
+    }
+
+    #[test]
+    fn test_natural_sorted_input_needs_no_merge() {
+        let mut count = 0;
+        let mut vec: Vec<i32> = (0..1_000).collect();
+        MergeSort {
+            variant: MergeVariant::Natural,
+            ..Default::default()
+        }
+        .sort_by(&mut vec, |a, b| {
+            count += 1;
+            a.cmp(b)
+        });
+        assert_eq!(count, 999);
+    }
+}
@@ -0,0 +1,1065 @@
+use std::cmp::Ordering;
+use std::mem;
+use std::thread;
+
+use super::apply_order;
+use super::heap::{heap_sort_by, heap_sort_probed};
+use super::insertion::insertion_sort_probed;
+use super::stats::{Counters, Probe, SortStats};
+use crate::prng::{Prng, RandomSource, Sampling};
+
+/// Slices at least this long use a ninther instead of a median of three.
+const NINTHER_THRESHOLD: usize = 128;
+
+/// The partitioning scheme used by [`QuickSort`].
+///
+/// Every scheme uses the last element as pivot and leaves it at the returned
+/// position, with no greater item before it and no smaller item after it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PartitionScheme {
+    /// Collect the smaller and larger items into fresh vectors, then write
+    /// them back. Stable, but allocates on every call.
+    Fold,
+    /// Sweep once from the left, swapping smaller items behind a boundary.
+    /// Like `Fold`, all items equal to the pivot end up after it.
+    #[default]
+    Lomuto,
+    /// Scan inwards from both ends and swap misplaced pairs. Does fewer swaps
+    /// than Lomuto and splits runs of equal items evenly.
+    Hoare,
+    /// Split into items smaller than, equal to and greater than the pivot
+    /// (Dijkstra's Dutch national flag). The equal run is excluded from
+    /// recursion, which pays off when keys repeat a lot.
+    ThreeWay,
+}
+
+/// How [`QuickSort`] picks the pivot before partitioning.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PivotStrategy {
+    /// Use the last element. Degrades to O(n²) on sorted input.
+    Last,
+    /// Use an element chosen by a [`Prng`].
+    Random,
+    /// Use the median of the first, middle and last elements.
+    #[default]
+    MedianOfThree,
+    /// Use the median of three medians of three (Tukey's ninther) on long
+    /// slices, and a median of three on short ones.
+    Ninther,
+}
+
+/// A configurable quicksort.
+///
+/// ```
+/// use algos::sort::quick::{PartitionScheme, PivotStrategy, QuickSort};
+///
+/// let mut vec = vec![3, 1, 2];
+/// QuickSort {
+///     scheme: PartitionScheme::Hoare,
+///     pivot: PivotStrategy::Random,
+///     ..Default::default()
+/// }
+/// .sort(&mut vec);
+/// assert_eq!(vec, vec![1, 2, 3]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuickSort {
+    pub scheme: PartitionScheme,
+    pub pivot: PivotStrategy,
+    /// Switch to heapsort once the recursion depth exceeds 2·log₂n, which
+    /// bounds the worst case to O(n log n).
+    pub introsort: bool,
+    /// Slices of at most this many items are finished with insertion sort.
+    pub insertion_threshold: usize,
+    /// Slices shorter than this are sorted on the current thread by the
+    /// parallel sorts.
+    pub par_cutoff: usize,
+}
+
+impl Default for QuickSort {
+    fn default() -> Self {
+        Self {
+            scheme: PartitionScheme::default(),
+            pivot: PivotStrategy::default(),
+            introsort: true,
+            insertion_threshold: 16,
+            par_cutoff: 10_000,
+        }
+    }
+}
+
+impl QuickSort {
+    /// Sort the slice in place.
+    pub fn sort<T: Ord>(&self, vec: &mut [T]) {
+        self.sort_by(vec, T::cmp);
+    }
+
+    /// Sort the slice in place, ordering items with `cmp`.
+    pub fn sort_by<T, F>(&self, vec: &mut [T], mut cmp: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        self.sort_rec(
+            vec,
+            &mut cmp,
+            &mut None,
+            self.depth_limit(vec.len()),
+            &(),
+            1,
+        );
+    }
+
+    /// Sort the slice in place and return the work it took.
+    pub fn sort_with_stats<T: Ord>(&self, vec: &mut [T]) -> SortStats {
+        self.sort_by_with_stats(vec, T::cmp)
+    }
+
+    /// Sort the slice in place, ordering items with `cmp`, and return the
+    /// work it took.
+    pub fn sort_by_with_stats<T, F>(&self, vec: &mut [T], mut cmp: F) -> SortStats
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let counters = Counters::default();
+        let mut counted = |a: &T, b: &T| {
+            counters.compared();
+            cmp(a, b)
+        };
+        let depth_limit = self.depth_limit(vec.len());
+        self.sort_rec(vec, &mut counted, &mut None, depth_limit, &counters, 1);
+        counters.stats()
+    }
+
+    /// Sort the slice in place, ordering items by `key`.
+    pub fn sort_by_key<T, K, F>(&self, vec: &mut [T], mut key: F)
+    where
+        K: Ord,
+        F: FnMut(&T) -> K,
+    {
+        self.sort_by(vec, |a, b| key(a).cmp(&key(b)));
+    }
+
+    /// Sort the slice in place, forking the recursive calls onto scoped
+    /// threads for slices of at least `par_cutoff` items.
+    ///
+    /// Unless the pivot is random, the result is identical to [`Self::sort`],
+    /// including the order of equal items. Random pivots come from one
+    /// [`Prng`] per call, and each forked thread gets a generator seeded from
+    /// it, so `PRNG_SEED` reproduces the run.
+    pub fn par_sort<T: Ord + Send>(&self, vec: &mut [T]) {
+        self.par_sort_by(vec, T::cmp);
+    }
+
+    /// Sort the slice in parallel, ordering items with `cmp`.
+    pub fn par_sort_by<T, F>(&self, vec: &mut [T], cmp: F)
+    where
+        T: Send,
+        F: Fn(&T, &T) -> Ordering + Sync,
+    {
+        let threads = thread::available_parallelism().map_or(1, |n| n.get());
+        let mut prng = (self.pivot == PivotStrategy::Random).then(Prng::new);
+        self.par_sort_rec(vec, &cmp, &mut prng, self.depth_limit(vec.len()), threads);
+    }
+
+    /// Sort the slice in parallel, ordering items by `key`.
+    pub fn par_sort_by_key<T, K, F>(&self, vec: &mut [T], key: F)
+    where
+        T: Send,
+        K: Ord,
+        F: Fn(&T) -> K + Sync,
+    {
+        self.par_sort_by(vec, |a, b| key(a).cmp(&key(b)));
+    }
+
+    /// Partition the slice around its last element with the configured scheme.
+    pub fn partition_by<T, F>(&self, vec: &mut [T], cmp: F) -> usize
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        self.partition_probed(vec, cmp, &()).0
+    }
+
+    /// Partition the slice around its last element with the configured scheme
+    /// and return the bounds `(lt, gt)` of the items equal to the pivot.
+    ///
+    /// Two-way schemes only report the pivot itself, as `(p, p + 1)`.
+    pub fn partition_bounds_by<T, F>(&self, vec: &mut [T], cmp: F) -> (usize, usize)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        self.partition_probed(vec, cmp, &())
+    }
+
+    /// Return the index of the pivot the configured strategy picks.
+    ///
+    /// The generator for [`PivotStrategy::Random`] is created on first use.
+    pub fn select_pivot<T, F>(&self, vec: &[T], cmp: &mut F, prng: &mut Option<Prng>) -> usize
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let len = vec.len();
+        let hi = len - 1;
+        match self.pivot {
+            PivotStrategy::Last => hi,
+            PivotStrategy::Random => prng.get_or_insert_with(Prng::new).index(len),
+            PivotStrategy::Ninther if len >= NINTHER_THRESHOLD => {
+                let step = len / 8;
+                let mid = len / 2;
+                let a = median_of_three(vec, 0, step, 2 * step, cmp);
+                let b = median_of_three(vec, mid - step, mid, mid + step, cmp);
+                let c = median_of_three(vec, hi - 2 * step, hi - step, hi, cmp);
+                median_of_three(vec, a, b, c, cmp)
+            }
+            PivotStrategy::MedianOfThree | PivotStrategy::Ninther => {
+                median_of_three(vec, 0, len / 2, hi, cmp)
+            }
+        }
+    }
+
+    // Partition like partition_bounds_by, reporting to probe.
+    fn partition_probed<T, F, P>(&self, vec: &mut [T], cmp: F, probe: &P) -> (usize, usize)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+        P: Probe,
+    {
+        let p = match self.scheme {
+            PartitionScheme::Fold => fold_partition(vec, cmp, probe),
+            PartitionScheme::Lomuto => lomuto_partition(vec, cmp, probe),
+            PartitionScheme::Hoare => hoare_partition(vec, cmp, probe),
+            PartitionScheme::ThreeWay => return three_way_partition(vec, cmp, probe),
+        };
+        (p, p + 1)
+    }
+
+    // The number of partitioning levels allowed before falling back to heapsort.
+    fn depth_limit(&self, len: usize) -> usize {
+        if self.introsort && len > 0 {
+            2 * len.ilog2() as usize
+        } else {
+            usize::MAX
+        }
+    }
+
+    // Like sort_rec, but hand one side of each split to another thread until
+    // the slices get short or every available thread is busy.
+    fn par_sort_rec<T, F>(
+        &self,
+        vec: &mut [T],
+        cmp: &F,
+        prng: &mut Option<Prng>,
+        depth_limit: usize,
+        threads: usize,
+    ) where
+        T: Send,
+        F: Fn(&T, &T) -> Ordering + Sync,
+    {
+        let mut cmp = cmp;
+        // Anything sort_rec would finish without partitioning goes to it
+        // directly, so both sorts split the slice in the same places.
+        if threads <= 1
+            || vec.len() < self.par_cutoff.max(3)
+            || vec.len() <= self.insertion_threshold
+        {
+            self.sort_rec(vec, &mut cmp, prng, depth_limit, &(), 1);
+            return;
+        }
+        if depth_limit == 0 {
+            heap_sort_by(vec, cmp);
+            return;
+        }
+
+        let pivot = self.select_pivot(vec, &mut cmp, prng);
+        let hi = vec.len() - 1;
+        vec.swap(pivot, hi);
+        let (lt, gt) = self.partition_bounds_by(vec, cmp);
+
+        let (lower, upper) = vec.split_at_mut(lt);
+        let upper = &mut upper[gt - lt..];
+        let forked = threads / 2;
+        // Seed the forked thread's generator from this one, so the pivots do
+        // not depend on how the threads are scheduled.
+        let mut forked_prng = prng.as_mut().map(|p| Prng::with_seed(p.next_u64()));
+        thread::scope(|scope| {
+            scope
+                .spawn(|| self.par_sort_rec(lower, cmp, &mut forked_prng, depth_limit - 1, forked));
+            self.par_sort_rec(upper, cmp, prng, depth_limit - 1, threads - forked);
+        });
+    }
+
+    // Sort vec, reporting to probe. depth is the number of calls on the stack,
+    // counting this one.
+    fn sort_rec<T, F, P>(
+        &self,
+        mut vec: &mut [T],
+        cmp: &mut F,
+        prng: &mut Option<Prng>,
+        mut depth_limit: usize,
+        probe: &P,
+        depth: usize,
+    ) where
+        F: FnMut(&T, &T) -> Ordering,
+        P: Probe,
+    {
+        probe.entered(depth);
+        loop {
+            if vec.len() <= self.insertion_threshold {
+                insertion_sort_probed(vec, &mut *cmp, probe);
+                return;
+            }
+            match vec.len() {
+                0 | 1 => return,
+                2 => {
+                    // if vec is not sorted, swap the two elements.
+                    if cmp(&vec[0], &vec[1]) == Ordering::Greater {
+                        vec.swap(0, 1);
+                        probe.swapped();
+                    }
+                    return;
+                }
+                _ => (),
+            }
+            if depth_limit == 0 {
+                heap_sort_probed(vec, &mut *cmp, probe);
+                return;
+            }
+            depth_limit -= 1;
+
+            let pivot = self.select_pivot(vec, cmp, prng);
+            let hi = vec.len() - 1;
+            if pivot != hi {
+                vec.swap(pivot, hi);
+                probe.swapped();
+            }
+            let (lt, gt) = self.partition_probed(vec, &mut *cmp, probe);
+
+            // Recurse into the smaller side and loop on the larger one, so the
+            // stack never grows deeper than log₂n frames.
+            let (lower, upper) = mem::take(&mut vec).split_at_mut(lt);
+            let upper = &mut upper[gt - lt..];
+            if lower.len() < upper.len() {
+                self.sort_rec(lower, cmp, prng, depth_limit, probe, depth + 1);
+                vec = upper;
+            } else {
+                self.sort_rec(upper, cmp, prng, depth_limit, probe, depth + 1);
+                vec = lower;
+            }
+        }
+    }
+}
+
+// Return whichever of the indices a, b and c holds the median value.
+fn median_of_three<T, F>(vec: &[T], a: usize, b: usize, c: usize, cmp: &mut F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mut less = |i: usize, j: usize| cmp(&vec[i], &vec[j]) == Ordering::Less;
+    if less(a, b) {
+        if less(b, c) {
+            b
+        } else if less(a, c) {
+            c
+        } else {
+            a
+        }
+    } else if less(a, c) {
+        a
+    } else if less(b, c) {
+        c
+    } else {
+        b
+    }
+}
+
+/// Sort the slice in place with quicksort, using several threads.
+pub fn par_quick_sort<T: Ord + Send>(vec: &mut [T]) {
+    QuickSort::default().par_sort(vec);
+}
+
+/// Sort the slice in place with quicksort on several threads, ordering items
+/// with `cmp`.
+pub fn par_quick_sort_by<T, F>(vec: &mut [T], cmp: F)
+where
+    T: Send,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
+    QuickSort::default().par_sort_by(vec, cmp);
+}
+
+/// Partition the slice around its last element.
+///
+/// Returns the final position of the pivot; every item before it is smaller
+/// and every item after it is greater or equal.
+pub fn partition<T: Ord>(vec: &mut [T]) -> usize {
+    partition_by(vec, T::cmp)
+}
+
+/// Partition the slice around its last element using `cmp` to order items.
+pub fn partition_by<T, F>(vec: &mut [T], cmp: F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    QuickSort::default().partition_by(vec, cmp)
+}
+
+/// Partition by copying the items into two vectors. See [`PartitionScheme::Fold`].
+pub fn fold_partition_by<T, F>(vec: &mut [T], cmp: F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    fold_partition(vec, cmp, &())
+}
+
+fn fold_partition<T, F, P>(vec: &mut [T], mut cmp: F, probe: &P) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+    P: Probe,
+{
+    let hi = vec.len() - 1;
+    let pivot = &vec[hi];
+    let (mut lower, mut higher) =
+        (0..hi).fold((Vec::new(), Vec::new()), |(mut lower, mut bigger), i| {
+            if cmp(&vec[i], pivot) == Ordering::Less {
+                lower.push(i);
+            } else {
+                bigger.push(i);
+            }
+            (lower, bigger)
+        });
+    let pos = lower.len();
+    lower.push(hi);
+    lower.append(&mut higher);
+    apply_order(vec, &lower);
+    // The two index vectors, and the one apply_order uses to move the items.
+    for _ in 0..3 {
+        probe.allocated();
+    }
+    probe.moved(vec.len());
+
+    pos
+}
+
+/// Partition in place with Lomuto's scheme. See [`PartitionScheme::Lomuto`].
+pub fn lomuto_partition_by<T, F>(vec: &mut [T], cmp: F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    lomuto_partition(vec, cmp, &())
+}
+
+fn lomuto_partition<T, F, P>(vec: &mut [T], mut cmp: F, probe: &P) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+    P: Probe,
+{
+    let hi = vec.len() - 1;
+    let mut store = 0;
+    for i in 0..hi {
+        if cmp(&vec[i], &vec[hi]) == Ordering::Less {
+            vec.swap(i, store);
+            probe.swapped();
+            store += 1;
+        }
+    }
+    vec.swap(store, hi);
+    probe.swapped();
+    store
+}
+
+/// Partition in place with Hoare's scheme. See [`PartitionScheme::Hoare`].
+pub fn hoare_partition_by<T, F>(vec: &mut [T], cmp: F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    hoare_partition(vec, cmp, &())
+}
+
+fn hoare_partition<T, F, P>(vec: &mut [T], mut cmp: F, probe: &P) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+    P: Probe,
+{
+    let hi = vec.len() - 1;
+    if hi == 0 {
+        return 0;
+    }
+
+    // Park the pivot at the front so the scans never move it.
+    vec.swap(0, hi);
+    probe.swapped();
+    let mut i = 0;
+    let mut j = hi + 1;
+    loop {
+        i += 1;
+        while i < hi && cmp(&vec[i], &vec[0]) == Ordering::Less {
+            i += 1;
+        }
+        j -= 1;
+        while cmp(&vec[0], &vec[j]) == Ordering::Less {
+            j -= 1;
+        }
+        if i >= j {
+            break;
+        }
+        vec.swap(i, j);
+        probe.swapped();
+    }
+    vec.swap(0, j);
+    probe.swapped();
+    j
+}
+
+/// Partition in place into three bands. See [`PartitionScheme::ThreeWay`].
+///
+/// Returns `(lt, gt)` such that `vec[..lt]` is smaller than the pivot,
+/// `vec[lt..gt]` equals it and `vec[gt..]` is greater.
+pub fn three_way_partition_by<T, F>(vec: &mut [T], cmp: F) -> (usize, usize)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    three_way_partition(vec, cmp, &())
+}
+
+fn three_way_partition<T, F, P>(vec: &mut [T], mut cmp: F, probe: &P) -> (usize, usize)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    P: Probe,
+{
+    // The pivot stays at hi until the scan is done.
+    let hi = vec.len() - 1;
+    let mut lt = 0;
+    let mut i = 0;
+    let mut gt = hi;
+    while i < gt {
+        match cmp(&vec[i], &vec[hi]) {
+            Ordering::Less => {
+                vec.swap(lt, i);
+                probe.swapped();
+                lt += 1;
+                i += 1;
+            }
+            Ordering::Greater => {
+                gt -= 1;
+                vec.swap(i, gt);
+                probe.swapped();
+            }
+            Ordering::Equal => i += 1,
+        }
+    }
+    vec.swap(gt, hi);
+    probe.swapped();
+    (lt, gt + 1)
+}
+
+/// Sort the slice in place with quicksort.
+pub fn quick_sort<T: Ord>(vec: &mut [T]) {
+    QuickSort::default().sort(vec);
+}
+
+/// Sort the slice in place with quicksort and return the work it took.
+pub fn quick_sort_with_stats<T: Ord>(vec: &mut [T]) -> SortStats {
+    QuickSort::default().sort_with_stats(vec)
+}
+
+/// Sort the slice in place with quicksort, ordering items with `cmp`.
+pub fn quick_sort_by<T, F>(vec: &mut [T], cmp: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    QuickSort::default().sort_by(vec, cmp);
+}
+
+/// Sort the slice in place with quicksort, ordering items by `key`.
+pub fn quick_sort_by_key<T, K, F>(vec: &mut [T], key: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    QuickSort::default().sort_by_key(vec, key);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::total_order::TotalF64;
+    use crate::util::{is_sorted, make_random_vec};
+
+    const SCHEMES: [PartitionScheme; 4] = [
+        PartitionScheme::Fold,
+        PartitionScheme::Lomuto,
+        PartitionScheme::Hoare,
+        PartitionScheme::ThreeWay,
+    ];
+
+    const PIVOTS: [PivotStrategy; 4] = [
+        PivotStrategy::Last,
+        PivotStrategy::Random,
+        PivotStrategy::MedianOfThree,
+        PivotStrategy::Ninther,
+    ];
+
+    // Sort vec with the given configuration and return the number of comparisons.
+    fn count_comparisons(sorter: QuickSort, vec: &mut [i32]) -> usize {
+        let mut comparisons = 0;
+        sorter.sort_by(vec, |a, b| {
+            comparisons += 1;
+            a.cmp(b)
+        });
+        comparisons
+    }
+
+    #[test]
+    fn test_partition() {
+        let mut vec = vec![3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5];
+        let p = partition(&mut vec);
+        assert_eq!(p, 6);
+    }
+
+    #[test]
+    fn test_quick_sort() {
+        let mut vec = vec![3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5];
+        quick_sort(&mut vec);
+        assert_eq!(vec, vec![1, 1, 2, 3, 3, 4, 5, 5, 5, 6, 9]);
+    }
+
+    #[test]
+    fn test_vectors_are_identical() {
+        let mut vec = vec![3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5];
+        let vec2 = vec.clone();
+        quick_sort(&mut vec);
+        let (identical, res) = vec.iter().fold((true, vec2), |(identical, mut vec2), x| {
+            if let Some(pos) = vec2.iter().position(|&y| y == *x) {
+                vec2.remove(pos);
+                (identical, vec2)
+            } else {
+                (false, vec2)
+            }
+        });
+        assert!(identical && res.is_empty());
+    }
+
+    #[test]
+    fn test_quick_sort_strings() {
+        let mut vec: Vec<String> = ["pear", "apple", "fig", "banana", "apple"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        quick_sort(&mut vec);
+        assert_eq!(vec, vec!["apple", "apple", "banana", "fig", "pear"]);
+    }
+
+    #[test]
+    fn test_quick_sort_by() {
+        let mut vec = vec![3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5];
+        quick_sort_by(&mut vec, |a, b| b.cmp(a));
+        assert_eq!(vec, vec![9, 6, 5, 5, 5, 4, 3, 3, 2, 1, 1]);
+    }
+
+    #[test]
+    fn test_quick_sort_by_key() {
+        #[derive(Debug, PartialEq)]
+        struct Record {
+            name: String,
+            age: u32,
+        }
+        let record = |name: &str, age| Record {
+            name: name.to_string(),
+            age,
+        };
+        let mut vec = vec![record("Ann", 42), record("Bob", 17), record("Cid", 30)];
+        quick_sort_by_key(&mut vec, |r| r.age);
+        assert_eq!(
+            vec,
+            vec![record("Bob", 17), record("Cid", 30), record("Ann", 42)]
+        );
+        quick_sort_by_key(&mut vec, |r| r.name.clone());
+        assert_eq!(vec[0].name, "Ann");
+    }
+
+    #[test]
+    fn test_quick_sort_f64() {
+        let mut vec: Vec<TotalF64> = [2.5, -1.0, f64::INFINITY, 0.0, -0.0, 1e-9]
+            .iter()
+            .map(|&x| TotalF64(x))
+            .collect();
+        quick_sort(&mut vec);
+        let sorted: Vec<f64> = vec.iter().map(|x| x.0).collect();
+        assert_eq!(sorted, vec![-1.0, -0.0, 0.0, 1e-9, 2.5, f64::INFINITY]);
+        assert!(sorted[1].is_sign_negative());
+    }
+
+    #[test]
+    fn test_partition_schemes() {
+        for scheme in SCHEMES {
+            let mut vec = vec![3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5];
+            let p = QuickSort {
+                scheme,
+                ..Default::default()
+            }
+            .partition_by(&mut vec, i32::cmp);
+            assert_eq!(vec[p], 5, "{scheme:?}");
+            assert!(vec[..p].iter().all(|&x| x <= 5), "{scheme:?}");
+            assert!(vec[p + 1..].iter().all(|&x| x >= 5), "{scheme:?}");
+        }
+    }
+
+    #[test]
+    fn test_partition_single_item() {
+        for scheme in SCHEMES {
+            let mut vec = vec![7];
+            assert_eq!(
+                QuickSort {
+                    scheme,
+                    ..Default::default()
+                }
+                .partition_by(&mut vec, i32::cmp),
+                0
+            );
+        }
+    }
+
+    #[test]
+    fn test_schemes_sort_random_vec() {
+        let vec = make_random_vec(10_000, 1_000);
+        for scheme in SCHEMES {
+            let mut sorted = vec.clone();
+            QuickSort {
+                scheme,
+                ..Default::default()
+            }
+            .sort(&mut sorted);
+            assert!(is_sorted(&sorted), "{scheme:?}");
+
+            let mut expected = vec.clone();
+            expected.sort();
+            assert_eq!(sorted, expected, "{scheme:?}");
+        }
+    }
+
+    #[test]
+    fn test_schemes_sort_equal_items() {
+        for scheme in SCHEMES {
+            let mut vec = vec![4; 500];
+            QuickSort {
+                scheme,
+                ..Default::default()
+            }
+            .sort(&mut vec);
+            assert_eq!(vec, vec![4; 500]);
+        }
+    }
+
+    #[test]
+    fn test_pivot_strategies_sort() {
+        let vec = make_random_vec(5_000, 100);
+        let mut expected = vec.clone();
+        expected.sort();
+        for pivot in PIVOTS {
+            for scheme in SCHEMES {
+                let mut sorted = vec.clone();
+                QuickSort {
+                    scheme,
+                    pivot,
+                    ..Default::default()
+                }
+                .sort(&mut sorted);
+                assert_eq!(sorted, expected, "{scheme:?} {pivot:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_median_of_three() {
+        let vec = [5, 1, 9];
+        for (a, b, c) in [
+            (0, 1, 2),
+            (0, 2, 1),
+            (1, 0, 2),
+            (1, 2, 0),
+            (2, 0, 1),
+            (2, 1, 0),
+        ] {
+            assert_eq!(median_of_three(&vec, a, b, c, &mut i32::cmp), 0);
+        }
+    }
+
+    #[test]
+    fn test_median_of_three_on_sorted_input() {
+        let mut vec: Vec<i32> = (0..10_000).collect();
+        let sorter = QuickSort {
+            pivot: PivotStrategy::MedianOfThree,
+            introsort: false,
+            ..Default::default()
+        };
+        // A balanced split needs about n·log₂n comparisons, far below n²/2.
+        assert!(count_comparisons(sorter, &mut vec) < 200_000);
+        assert!(is_sorted(&vec));
+    }
+
+    #[test]
+    fn test_introsort_bounds_worst_case() {
+        let n = 10_000;
+        let inputs: [Vec<i32>; 3] = [
+            (0..n).collect(),
+            (0..n).rev().collect(),
+            vec![7; n as usize],
+        ];
+        for input in inputs {
+            for scheme in SCHEMES {
+                let mut vec = input.clone();
+                let sorter = QuickSort {
+                    scheme,
+                    pivot: PivotStrategy::Last,
+                    introsort: true,
+                    ..Default::default()
+                };
+                // Quadratic behaviour would need about 5·10⁷ comparisons.
+                assert!(
+                    count_comparisons(sorter, &mut vec) < 1_000_000,
+                    "{scheme:?}"
+                );
+                assert!(is_sorted(&vec));
+            }
+        }
+    }
+
+    #[test]
+    fn test_deep_recursion_does_not_overflow() {
+        // Without introsort every partition is maximally unbalanced, but the
+        // larger side is handled by the loop rather than a recursive call.
+        let mut vec: Vec<i32> = (0..3_000).collect();
+        QuickSort {
+            pivot: PivotStrategy::Last,
+            introsort: false,
+            ..Default::default()
+        }
+        .sort(&mut vec);
+        assert!(is_sorted(&vec));
+    }
+
+    #[test]
+    fn test_three_way_partition() {
+        let mut vec = vec![5, 3, 5, 1, 9, 5, 2, 6, 5, 3, 5];
+        let (lt, gt) = three_way_partition_by(&mut vec, i32::cmp);
+        assert_eq!((lt, gt), (4, 9));
+        assert!(vec[..lt].iter().all(|&x| x < 5));
+        assert!(vec[lt..gt].iter().all(|&x| x == 5));
+        assert!(vec[gt..].iter().all(|&x| x > 5));
+    }
+
+    #[test]
+    fn test_three_way_partition_bounds() {
+        let sorter = QuickSort {
+            scheme: PartitionScheme::ThreeWay,
+            ..Default::default()
+        };
+        let mut vec = vec![2, 2, 2];
+        assert_eq!(sorter.partition_bounds_by(&mut vec, i32::cmp), (0, 3));
+
+        let two_way = QuickSort::default();
+        let mut vec = vec![3, 1, 2];
+        assert_eq!(two_way.partition_bounds_by(&mut vec, i32::cmp), (1, 2));
+    }
+
+    #[test]
+    fn test_three_way_duplicate_heavy() {
+        let n = 100_000;
+        let vec = make_random_vec(n, 10);
+        let mut expected = vec.clone();
+        expected.sort();
+
+        let mut sorted = vec.clone();
+        let sorter = QuickSort {
+            scheme: PartitionScheme::ThreeWay,
+            introsort: false,
+            ..Default::default()
+        };
+        let comparisons = count_comparisons(sorter, &mut sorted);
+        assert_eq!(sorted, expected);
+        // With only ten distinct keys there are only a handful of levels.
+        assert!(comparisons < 10 * n as usize, "{comparisons} comparisons");
+    }
+
+    #[test]
+    fn test_three_way_tiny_max() {
+        for max in [1, 2, 3] {
+            let mut vec = make_random_vec(10_000, max);
+            let mut expected = vec.clone();
+            expected.sort();
+            QuickSort {
+                scheme: PartitionScheme::ThreeWay,
+                ..Default::default()
+            }
+            .sort(&mut vec);
+            assert_eq!(vec, expected, "max {max}");
+        }
+    }
+
+    #[test]
+    fn test_par_quick_sort() {
+        let mut vec = make_random_vec(100_000, 1_000);
+        let mut expected = vec.clone();
+        expected.sort();
+        par_quick_sort(&mut vec);
+        assert_eq!(vec, expected);
+    }
+
+    #[test]
+    fn test_par_sort_matches_sequential() {
+        // Sorting by key leaves ties distinguishable by their second field,
+        // so any difference in the order of equal keys shows up here.
+        let keys = make_random_vec(50_000, 50);
+        let vec: Vec<(i32, usize)> = keys.into_iter().zip(0..).collect();
+        for scheme in SCHEMES {
+            for pivot in [PivotStrategy::Last, PivotStrategy::Ninther] {
+                // A cutoff below the insertion threshold must not make the
+                // parallel sort partition what sort would insertion-sort.
+                for par_cutoff in [4, 1_000] {
+                    let sorter = QuickSort {
+                        scheme,
+                        pivot,
+                        par_cutoff,
+                        ..Default::default()
+                    };
+                    let mut sequential = vec.clone();
+                    sorter.sort_by_key(&mut sequential, |x| x.0);
+                    let mut parallel = vec.clone();
+                    sorter.par_sort_by_key(&mut parallel, |x| x.0);
+                    assert_eq!(parallel, sequential, "{scheme:?} {pivot:?} {par_cutoff}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_par_sort_random_pivot_is_reproducible() {
+        let keys = make_random_vec(50_000, 50);
+        let vec: Vec<(i32, usize)> = keys.into_iter().zip(0..).collect();
+        let sorter = QuickSort {
+            pivot: PivotStrategy::Random,
+            par_cutoff: 1_000,
+            ..Default::default()
+        };
+        let depth_limit = sorter.depth_limit(vec.len());
+        let cmp = |a: &(i32, usize), b: &(i32, usize)| a.0.cmp(&b.0);
+        // Force a fork at every level, whatever the machine offers.
+        let runs: Vec<_> = (0..2)
+            .map(|_| {
+                let mut sorted = vec.clone();
+                let mut prng = Some(Prng::with_seed(7));
+                sorter.par_sort_rec(&mut sorted, &cmp, &mut prng, depth_limit, 16);
+                sorted
+            })
+            .collect();
+        assert!(runs[0].windows(2).all(|w| w[0].0 <= w[1].0));
+        assert_eq!(runs[0], runs[1]);
+    }
+
+    #[test]
+    fn test_random_pivot_is_in_range() {
+        let sorter = QuickSort {
+            pivot: PivotStrategy::Random,
+            ..Default::default()
+        };
+        let mut prng = Some(Prng::with_seed(1));
+        for len in 1..50 {
+            let vec = vec![0; len];
+            let pivot = sorter.select_pivot(&vec, &mut i32::cmp, &mut prng);
+            assert!(pivot < len);
+        }
+    }
+
+    #[test]
+    fn test_par_sort_small_slices() {
+        for len in 0..8 {
+            let mut vec: Vec<i32> = (0..len).rev().collect();
+            QuickSort {
+                par_cutoff: 0,
+                ..Default::default()
+            }
+            .par_sort(&mut vec);
+            assert_eq!(vec, (0..len).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn test_insertion_thresholds() {
+        let vec = make_random_vec(5_000, 1_000);
+        let mut expected = vec.clone();
+        expected.sort();
+        for insertion_threshold in [0, 1, 2, 3, 4, 16, 64] {
+            for scheme in SCHEMES {
+                let mut sorted = vec.clone();
+                QuickSort {
+                    scheme,
+                    insertion_threshold,
+                    ..Default::default()
+                }
+                .sort(&mut sorted);
+                assert_eq!(sorted, expected, "{scheme:?} {insertion_threshold}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_sort_with_stats() {
+        let vec = make_random_vec(10_000, 1_000);
+        for scheme in SCHEMES {
+            for pivot in [PivotStrategy::Last, PivotStrategy::MedianOfThree] {
+                let sorter = QuickSort {
+                    scheme,
+                    pivot,
+                    ..Default::default()
+                };
+                let mut counted = vec.clone();
+                let stats = sorter.sort_with_stats(&mut counted);
+                assert!(is_sorted(&counted));
+                // The comparator sees the same calls either way.
+                let mut plain = vec.clone();
+                assert_eq!(
+                    stats.comparisons,
+                    count_comparisons(sorter, &mut plain) as u64
+                );
+                assert_eq!(counted, plain);
+
+                assert!(stats.swaps > 0, "{scheme:?} {pivot:?}");
+                // Recursing into the smaller side bounds the depth by log₂n.
+                assert!((1..=14).contains(&stats.max_depth), "{stats}");
+                let fold = scheme == PartitionScheme::Fold;
+                assert_eq!(stats.allocations > 0, fold, "{scheme:?}");
+                assert_eq!(stats.moves > 0, fold, "{scheme:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_stats_count_fallbacks() {
+        // Insertion sort alone: one swap per inversion.
+        let mut vec = vec![3, 2, 1];
+        let stats = quick_sort_with_stats(&mut vec);
+        assert_eq!(vec, vec![1, 2, 3]);
+        assert_eq!(
+            stats,
+            SortStats {
+                comparisons: 3,
+                swaps: 3,
+                max_depth: 1,
+                ..Default::default()
+            }
+        );
+
+        // Sorted input with the last element as pivot exhausts the depth
+        // limit, and heapsort's swaps are counted from there on. Every split
+        // leaves one side empty, so only empty slices are recursed into.
+        let sorter = QuickSort {
+            pivot: PivotStrategy::Last,
+            insertion_threshold: 0,
+            ..Default::default()
+        };
+        let mut vec: Vec<i32> = (0..1_000).collect();
+        let stats = sorter.sort_with_stats(&mut vec);
+        assert!(is_sorted(&vec));
+        assert_eq!(stats.max_depth, 2, "{stats}");
+        assert!(stats.swaps > 1_000, "{stats}");
+        assert_eq!(quick_sort_with_stats::<i32>(&mut []).max_depth, 1);
+    }
+}
@@ -0,0 +1,318 @@
+use super::apply_order;
+use super::counting::counting_pass;
+use super::insertion::insertion_sort_by;
+
+/// The number of key bits each radix sort pass looks at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Radix {
+    /// 256 buckets per pass.
+    #[default]
+    Bits8,
+    /// 65536 buckets per pass; half as many passes, but a bigger count table.
+    Bits16,
+}
+
+impl Radix {
+    /// The number of bits in one digit.
+    pub fn bits(self) -> u32 {
+        match self {
+            Radix::Bits8 => 8,
+            Radix::Bits16 => 16,
+        }
+    }
+
+    // MSD buckets at most this long are finished with insertion sort.
+    fn msd_cutoff(self) -> usize {
+        match self {
+            Radix::Bits8 => 32,
+            Radix::Bits16 => 1024,
+        }
+    }
+}
+
+/// A fixed-width integer that LSD radix sort can order digit by digit.
+pub trait RadixKey: Copy {
+    /// The width of the key in bits.
+    const BITS: u32;
+
+    /// Map the value to an unsigned key with the same ordering.
+    fn radix_key(self) -> u64;
+}
+
+impl RadixKey for u32 {
+    const BITS: u32 = 32;
+
+    fn radix_key(self) -> u64 {
+        self as u64
+    }
+}
+
+impl RadixKey for u64 {
+    const BITS: u32 = 64;
+
+    fn radix_key(self) -> u64 {
+        self
+    }
+}
+
+impl RadixKey for i32 {
+    const BITS: u32 = 32;
+
+    fn radix_key(self) -> u64 {
+        // Flipping the sign bit puts negative values below positive ones.
+        (self as u32 ^ (1 << 31)) as u64
+    }
+}
+
+impl RadixKey for i64 {
+    const BITS: u32 = 64;
+
+    fn radix_key(self) -> u64 {
+        self as u64 ^ (1 << 63)
+    }
+}
+
+/// Sort the integers in place with least-significant-digit radix sort.
+///
+/// Every pass is a stable counting sort on one digit, starting with the
+/// lowest. Passes where all items share the same digit are skipped.
+pub fn lsd_radix_sort<T: RadixKey>(vec: &mut [T], radix: Radix) {
+    lsd_radix_sort_by_key(vec, radix, |&x| x);
+}
+
+/// Sort the items in place by an integer key with least-significant-digit
+/// radix sort. The sort is stable.
+pub fn lsd_radix_sort_by_key<T, K, F>(vec: &mut [T], radix: Radix, key: F)
+where
+    T: Copy,
+    K: RadixKey,
+    F: Fn(&T) -> K,
+{
+    let bits = radix.bits();
+    let mask = (1u64 << bits) - 1;
+    let mut counts = vec![0; 1 << bits];
+    let mut scratch = vec.to_vec();
+
+    // Ping-pong between vec and the scratch buffer.
+    let mut in_scratch = false;
+    for shift in (0..K::BITS).step_by(bits as usize) {
+        let digit = |x: &T| ((key(x).radix_key() >> shift) & mask) as usize;
+        let src: &[T] = if in_scratch { &scratch } else { vec };
+        if src.windows(2).all(|w| digit(&w[0]) == digit(&w[1])) {
+            continue;
+        }
+        if in_scratch {
+            counting_pass(&scratch, vec, &mut counts, digit);
+        } else {
+            counting_pass(vec, &mut scratch, &mut counts, digit);
+        }
+        in_scratch = !in_scratch;
+    }
+    if in_scratch {
+        vec.copy_from_slice(&scratch);
+    }
+}
+
+/// Sort the byte strings in place with most-significant-digit radix sort.
+///
+/// Strings are bucketed by their leading digit and each bucket is sorted
+/// recursively on the following digits. A string sorts before any longer
+/// string it is a prefix of.
+pub fn msd_radix_sort<S: AsRef<[u8]>>(vec: &mut [S], radix: Radix) {
+    let mut order: Vec<usize> = (0..vec.len()).collect();
+    let mut scratch = order.clone();
+    {
+        let strings: Vec<&[u8]> = vec.iter().map(|s| s.as_ref()).collect();
+        msd_sort(&strings, &mut order, &mut scratch, radix);
+    }
+    apply_order(vec, &order);
+}
+
+// Sort the indices in order by the strings they point to, looking only at
+// the bytes from depth onwards. Buckets still to be sorted wait on an explicit
+// stack rather than in recursive calls, so strings with long shared prefixes
+// cannot overflow the call stack, and one count table serves every pass.
+fn msd_sort(strings: &[&[u8]], order: &mut [usize], scratch: &mut [usize], radix: Radix) {
+    let step = (radix.bits() / 8) as usize;
+    let mut counts = vec![0; num_string_digits(radix)];
+    let mut pending = vec![(0, order.len(), 0)];
+    while let Some((lo, hi, depth)) = pending.pop() {
+        let (order, scratch) = (&mut order[lo..hi], &mut scratch[lo..hi]);
+        if order.len() <= radix.msd_cutoff() {
+            insertion_sort_by(order, |&a, &b| {
+                strings[a][depth..].cmp(&strings[b][depth..])
+            });
+            continue;
+        }
+
+        let digit = |&i: &usize| string_digit(strings[i], depth, radix);
+        counting_pass(order, scratch, &mut counts, digit);
+        order.copy_from_slice(scratch);
+
+        // counts[d] now holds the end of bucket d.
+        let mut start = 0;
+        for (d, &end) in counts.iter().enumerate() {
+            if end - start > 1 && digit_continues(d, radix) {
+                pending.push((lo + start, lo + end, depth + step));
+            }
+            start = end;
+        }
+    }
+}
+
+// The number of distinct values string_digit can return.
+fn num_string_digits(radix: Radix) -> usize {
+    match radix {
+        Radix::Bits8 => 1 + 256,
+        Radix::Bits16 => 1 + 256 * 257,
+    }
+}
+
+// Return the digit of s at byte offset depth. Zero means the string has
+// ended, so shorter strings come first.
+fn string_digit(s: &[u8], depth: usize, radix: Radix) -> usize {
+    let rest = s.get(depth..).unwrap_or_default();
+    match (radix, rest) {
+        (_, []) => 0,
+        (Radix::Bits8, [b0, ..]) => 1 + *b0 as usize,
+        // A string ending after b0 sorts between the ended strings and the
+        // ones continuing with (b0, 0).
+        (Radix::Bits16, [b0]) => 1 + *b0 as usize * 257,
+        (Radix::Bits16, [b0, b1, ..]) => 2 + *b0 as usize * 257 + *b1 as usize,
+    }
+}
+
+// Return true if strings with digit d may still differ after this digit.
+fn digit_continues(d: usize, radix: Radix) -> bool {
+    match radix {
+        Radix::Bits8 => d != 0,
+        Radix::Bits16 => d != 0 && !(d - 1).is_multiple_of(257),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::make_random_vec;
+
+    const RADIXES: [Radix; 2] = [Radix::Bits8, Radix::Bits16];
+
+    #[test]
+    fn test_lsd_radix_sort_i32() {
+        let vec = make_random_vec(10_000, 1_000_000);
+        let mut vec: Vec<i32> = vec.iter().map(|x| x - 500_000).collect();
+        vec.extend([i32::MIN, i32::MAX, 0, -1]);
+        let mut expected = vec.clone();
+        expected.sort();
+        for radix in RADIXES {
+            let mut sorted = vec.clone();
+            lsd_radix_sort(&mut sorted, radix);
+            assert_eq!(sorted, expected, "{radix:?}");
+        }
+    }
+
+    #[test]
+    fn test_lsd_radix_sort_wide_keys() {
+        let vec = make_random_vec(5_000, i32::MAX);
+        let unsigned: Vec<u64> = vec.iter().map(|&x| (x as u64) << 31 | x as u64).collect();
+        let signed: Vec<i64> = vec.iter().map(|&x| (x as i64 - (1 << 30)) << 32).collect();
+        for radix in RADIXES {
+            let mut sorted = unsigned.clone();
+            lsd_radix_sort(&mut sorted, radix);
+            let mut expected = unsigned.clone();
+            expected.sort();
+            assert_eq!(sorted, expected, "{radix:?}");
+
+            let mut sorted = signed.clone();
+            lsd_radix_sort(&mut sorted, radix);
+            let mut expected = signed.clone();
+            expected.sort();
+            assert_eq!(sorted, expected, "{radix:?}");
+        }
+    }
+
+    #[test]
+    fn test_lsd_radix_sort_small() {
+        for radix in RADIXES {
+            let mut empty: Vec<u32> = Vec::new();
+            lsd_radix_sort(&mut empty, radix);
+            assert!(empty.is_empty());
+
+            let mut vec = vec![3u32, 1, 2];
+            lsd_radix_sort(&mut vec, radix);
+            assert_eq!(vec, vec![1, 2, 3]);
+        }
+    }
+
+    #[test]
+    fn test_lsd_radix_sort_by_key_is_stable() {
+        let vec = make_random_vec(2_000, 50);
+        let mut pairs: Vec<(i64, usize)> = vec.iter().map(|&x| (x as i64 - 25, 0)).collect();
+        for (i, pair) in pairs.iter_mut().enumerate() {
+            pair.1 = i;
+        }
+        let mut expected = pairs.clone();
+        expected.sort();
+        for radix in RADIXES {
+            let mut sorted = pairs.clone();
+            lsd_radix_sort_by_key(&mut sorted, radix, |&(k, _)| k);
+            assert_eq!(sorted, expected, "{radix:?}");
+        }
+    }
+
+    #[test]
+    fn test_msd_radix_sort_strings() {
+        let words = [
+            "banana", "", "band", "ban", "b", "apple", "ban", "a\0", "a", "bandana",
+        ];
+        for radix in RADIXES {
+            let mut vec: Vec<String> = words.iter().map(|w| w.to_string()).collect();
+            msd_radix_sort(&mut vec, radix);
+            let mut expected: Vec<String> = words.iter().map(|w| w.to_string()).collect();
+            expected.sort();
+            assert_eq!(vec, expected, "{radix:?}");
+        }
+    }
+
+    #[test]
+    fn test_msd_radix_sort_many_strings() {
+        // Enough strings sharing prefixes to go past the insertion cutoff.
+        let vec: Vec<Vec<u8>> = make_random_vec(5_000, 100_000)
+            .iter()
+            .map(|x| format!("key{}", x).into_bytes())
+            .collect();
+        let mut expected = vec.clone();
+        expected.sort();
+        for radix in RADIXES {
+            let mut sorted = vec.clone();
+            msd_radix_sort(&mut sorted, radix);
+            assert_eq!(sorted, expected, "{radix:?}");
+        }
+    }
+
+    #[test]
+    fn test_string_digit_order() {
+        // "a" < "a\0" < "a\u{1}" < "b"
+        let radix = Radix::Bits16;
+        let digits: Vec<usize> = [&b"a"[..], b"a\0", b"a\x01", b"b"]
+            .iter()
+            .map(|s| string_digit(s, 0, radix))
+            .collect();
+        assert!(digits.windows(2).all(|w| w[0] < w[1]));
+        assert!(digits.iter().all(|&d| d < num_string_digits(radix)));
+        assert!(!digit_continues(digits[0], radix));
+        assert!(digit_continues(digits[1], radix));
+    }
+
+    #[test]
+    fn test_msd_radix_sort_long_shared_prefix() {
+        // One digit per byte of the prefix would once have meant one stack
+        // frame per byte.
+        let prefix = "x".repeat(50_000);
+        let mut vec: Vec<String> = (0..40).rev().map(|i| format!("{prefix}{i:02}")).collect();
+        let mut expected = vec.clone();
+        expected.sort();
+        msd_radix_sort(&mut vec, Radix::Bits8);
+        assert_eq!(vec, expected);
+    }
+}
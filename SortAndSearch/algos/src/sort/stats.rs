@@ -0,0 +1,85 @@
+use std::cell::Cell;
+use std::fmt;
+
+/// The work done by one run of an instrumented sort.
+///
+/// Comparisons are between items. Swaps exchange two items in place, while
+/// moves count items copied or written into place some other way, such as
+/// by counting sort. Allocations count the buffers the sort created, and the
+/// maximum depth is the deepest nesting of recursive calls, where the first
+/// call is depth 1 and sorts without recursion report 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SortStats {
+    pub comparisons: u64,
+    pub swaps: u64,
+    pub moves: u64,
+    pub allocations: u64,
+    pub max_depth: usize,
+}
+
+impl fmt::Display for SortStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} comparisons, {} swaps, {} moves, {} allocations, max depth {}",
+            self.comparisons, self.swaps, self.moves, self.allocations, self.max_depth
+        )
+    }
+}
+
+// Receives the events of an instrumented sort. The uninstrumented sorts pass
+// (), whose methods compile to nothing. Comparisons are counted by wrapping
+// the comparator instead.
+pub(crate) trait Probe {
+    fn swapped(&self) {}
+    fn moved(&self, _items: usize) {}
+    fn allocated(&self) {}
+    fn entered(&self, _depth: usize) {}
+}
+
+impl Probe for () {}
+
+// A probe that adds up the events. The counts are cells so that the probe
+// can be shared with the comparator while the sort reports to it.
+#[derive(Debug, Default)]
+pub(crate) struct Counters {
+    comparisons: Cell<u64>,
+    swaps: Cell<u64>,
+    moves: Cell<u64>,
+    allocations: Cell<u64>,
+    max_depth: Cell<usize>,
+}
+
+impl Counters {
+    pub(crate) fn compared(&self) {
+        self.comparisons.set(self.comparisons.get() + 1);
+    }
+
+    pub(crate) fn stats(&self) -> SortStats {
+        SortStats {
+            comparisons: self.comparisons.get(),
+            swaps: self.swaps.get(),
+            moves: self.moves.get(),
+            allocations: self.allocations.get(),
+            max_depth: self.max_depth.get(),
+        }
+    }
+}
+
+impl Probe for Counters {
+    fn swapped(&self) {
+        self.swaps.set(self.swaps.get() + 1);
+    }
+
+    fn moved(&self, items: usize) {
+        self.moves.set(self.moves.get() + items as u64);
+    }
+
+    fn allocated(&self) {
+        self.allocations.set(self.allocations.get() + 1);
+    }
+
+    fn entered(&self, depth: usize) {
+        self.max_depth.set(self.max_depth.get().max(depth));
+    }
+}
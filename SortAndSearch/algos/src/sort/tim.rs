@@ -0,0 +1,383 @@
+use std::cmp::Ordering;
+use std::mem;
+
+use super::merge::find_run;
+
+// Inputs shorter than this are sorted by binary insertion sort alone.
+const MIN_MERGE: usize = 64;
+
+// A merge switches to galloping once one run has supplied this many items in
+// a row. The threshold adapts as the sort goes on.
+const MIN_GALLOP: usize = 7;
+
+/// Sort the slice in place with an adaptive, stable merge sort modelled on
+/// Timsort.
+///
+/// Ascending and strictly descending runs already in the input are found and
+/// kept, short runs are extended with binary insertion sort, and merges
+/// gallop through long stretches taken from one run. Sorted and reverse
+/// sorted input take O(n) time; the worst case is O(n log n).
+pub fn tim_sort<T: Ord + Clone>(vec: &mut [T]) {
+    tim_sort_by(vec, T::cmp);
+}
+
+/// Sort the slice in place with [`tim_sort`], ordering items by `key`.
+pub fn tim_sort_by_key<T, K, F>(vec: &mut [T], mut key: F)
+where
+    T: Clone,
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    tim_sort_by(vec, |a, b| key(a).cmp(&key(b)));
+}
+
+/// Sort the slice in place with [`tim_sort`], ordering items with `cmp`.
+pub fn tim_sort_by<T, F>(vec: &mut [T], mut cmp: F)
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let len = vec.len();
+    if len < 2 {
+        return;
+    }
+    let min_run = min_run_length(len);
+    let mut merger = Merger {
+        runs: Vec::new(),
+        scratch: Vec::new(),
+        min_gallop: MIN_GALLOP,
+    };
+    let mut start = 0;
+    while start < len {
+        let mut run = find_run(&mut vec[start..], &mut cmp);
+        if run < min_run {
+            let forced = min_run.min(len - start);
+            binary_insertion_sort(&mut vec[start..start + forced], run, &mut cmp);
+            run = forced;
+        }
+        merger.runs.push(Run { start, len: run });
+        merger.collapse(vec, &mut cmp);
+        start += run;
+    }
+    merger.force_collapse(vec, &mut cmp);
+}
+
+// Return a run length between MIN_MERGE / 2 and MIN_MERGE such that len /
+// min_run is a power of two or slightly less, which keeps the final merges
+// balanced.
+fn min_run_length(mut len: usize) -> usize {
+    let mut extra = 0;
+    while len >= MIN_MERGE {
+        extra |= len & 1;
+        len >>= 1;
+    }
+    len + extra
+}
+
+// Sort vec given that vec[..sorted] is already sorted. Each item is inserted
+// after any equal items, which keeps the sort stable.
+fn binary_insertion_sort<T, F>(vec: &mut [T], sorted: usize, cmp: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    for i in sorted.max(1)..vec.len() {
+        let (before, rest) = vec.split_at(i);
+        let pos = before.partition_point(|x| cmp(x, &rest[0]) != Ordering::Greater);
+        vec[pos..=i].rotate_right(1);
+    }
+}
+
+// Return the length of the prefix of slice for which pred holds, given that
+// pred holds for a prefix and fails for the rest. Probing at 1, 2, 4, ...
+// before the binary search makes short prefixes cheap to find.
+fn gallop<T>(slice: &[T], mut pred: impl FnMut(&T) -> bool) -> usize {
+    let mut bound = 1;
+    while bound <= slice.len() && pred(&slice[bound - 1]) {
+        bound *= 2;
+    }
+    let lo = bound / 2;
+    let hi = (bound - 1).min(slice.len());
+    lo + slice[lo..hi].partition_point(pred)
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Run {
+    start: usize,
+    len: usize,
+}
+
+// The runs waiting to be merged, from left to right, and the state shared by
+// all merges.
+struct Merger<T> {
+    runs: Vec<Run>,
+    // Holds the left run during a merge. It only grows, and its items are
+    // placeholders between merges.
+    scratch: Vec<T>,
+    min_gallop: usize,
+}
+
+impl<T: Clone> Merger<T> {
+    // Merge runs until each run is longer than the one after it and than the
+    // two after it combined, so the stack stays O(log n) deep and merges
+    // stay balanced.
+    fn collapse<F>(&mut self, vec: &mut [T], cmp: &mut F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        while self.runs.len() > 1 {
+            let n = self.runs.len();
+            let len = |i: usize| self.runs[i].len;
+            let merge_at = if (n >= 3 && len(n - 3) <= len(n - 2) + len(n - 1))
+                || (n >= 4 && len(n - 4) <= len(n - 3) + len(n - 2))
+            {
+                if len(n - 3) < len(n - 1) {
+                    n - 3
+                } else {
+                    n - 2
+                }
+            } else if len(n - 2) <= len(n - 1) {
+                n - 2
+            } else {
+                break;
+            };
+            self.merge_at(vec, merge_at, cmp);
+        }
+    }
+
+    // Merge the remaining runs into one.
+    fn force_collapse<F>(&mut self, vec: &mut [T], cmp: &mut F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        while self.runs.len() > 1 {
+            let n = self.runs.len();
+            if n >= 3 && self.runs[n - 3].len < self.runs[n - 1].len {
+                self.merge_at(vec, n - 3, cmp);
+            } else {
+                self.merge_at(vec, n - 2, cmp);
+            }
+        }
+    }
+
+    // Merge run i with run i + 1.
+    fn merge_at<F>(&mut self, vec: &mut [T], i: usize, cmp: &mut F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let left = self.runs[i];
+        let right = self.runs.remove(i + 1);
+        self.runs[i].len += right.len;
+        let end = right.start + right.len;
+        self.merge(&mut vec[left.start..end], left.len, cmp);
+    }
+
+    // Merge the sorted runs vec[..mid] and vec[mid..].
+    fn merge<F>(&mut self, vec: &mut [T], mid: usize, cmp: &mut F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        // Items of the left run that are not greater than the first item of
+        // the right run are already in place, as are items of the right run
+        // that are not less than the last item of the left run.
+        let (left, right) = vec.split_at(mid);
+        let skip = gallop(left, |x| cmp(x, &right[0]) != Ordering::Greater);
+        if skip == mid {
+            return;
+        }
+        let keep = gallop(right, |x| cmp(x, &left[mid - 1]) == Ordering::Less);
+        self.merge_lo(&mut vec[skip..mid + keep], mid - skip, cmp);
+    }
+
+    // Merge by moving the left run into scratch and filling vec from the
+    // front. Items move by swapping, so nothing is cloned once scratch is
+    // long enough.
+    fn merge_lo<F>(&mut self, vec: &mut [T], mid: usize, cmp: &mut F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let have = self.scratch.len();
+        if have < mid {
+            self.scratch.extend_from_slice(&vec[have..mid]);
+        }
+        let left = &mut self.scratch[..mid];
+        left.swap_with_slice(&mut vec[..mid]);
+
+        // vec[..k] is merged, left[i..] and vec[j..] are still to come, and
+        // vec[k..j] holds placeholders.
+        let len = vec.len();
+        let (mut i, mut j, mut k) = (0, mid, 0);
+        let mut min_gallop = self.min_gallop;
+        'merge: loop {
+            // Take one item at a time until one run keeps winning.
+            let (mut left_wins, mut right_wins) = (0, 0);
+            while left_wins < min_gallop && right_wins < min_gallop {
+                if cmp(&vec[j], &left[i]) == Ordering::Less {
+                    vec.swap(k, j);
+                    j += 1;
+                    right_wins += 1;
+                    left_wins = 0;
+                } else {
+                    mem::swap(&mut vec[k], &mut left[i]);
+                    i += 1;
+                    left_wins += 1;
+                    right_wins = 0;
+                }
+                k += 1;
+                if i == mid || j == len {
+                    break 'merge;
+                }
+            }
+
+            // Gallop: find how many items in a row each run supplies and move
+            // them as a block, until the blocks get short again.
+            loop {
+                let n = gallop(&left[i..], |x| cmp(x, &vec[j]) != Ordering::Greater);
+                for t in 0..n {
+                    mem::swap(&mut vec[k + t], &mut left[i + t]);
+                }
+                i += n;
+                k += n;
+                if i == mid {
+                    break 'merge;
+                }
+                let m = gallop(&vec[j..], |x| cmp(x, &left[i]) == Ordering::Less);
+                // The blocks may overlap, but swapping front to back still
+                // moves each item to its place.
+                for t in 0..m {
+                    vec.swap(k + t, j + t);
+                }
+                j += m;
+                k += m;
+                if j == len {
+                    break 'merge;
+                }
+                if n < MIN_GALLOP && m < MIN_GALLOP {
+                    min_gallop += 1;
+                    break;
+                }
+                min_gallop = min_gallop.saturating_sub(1).max(1);
+            }
+        }
+        // The rest of the right run is already in place.
+        vec[k..k + mid - i].swap_with_slice(&mut left[i..]);
+        self.min_gallop = min_gallop;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sort::bubble_sort;
+    use crate::util::make_random_vec;
+    use crate::{Distribution, Prng, Sampling};
+
+    #[test]
+    fn test_matches_bubble_sort() {
+        let mut prng = Prng::with_seed(23);
+        for dist in [
+            Distribution::NearlySorted { swaps: 10 },
+            Distribution::NearlySorted { swaps: 200 },
+            Distribution::Reverse,
+        ] {
+            for num_items in [10, 100, 1_500] {
+                let vec = dist.generate(&mut prng, num_items, 1_000);
+                let mut expected = vec.clone();
+                bubble_sort(&mut expected);
+                let mut sorted = vec;
+                tim_sort(&mut sorted);
+                assert_eq!(sorted, expected, "{dist} with {num_items} items");
+            }
+        }
+    }
+
+    #[test]
+    fn test_every_distribution() {
+        let mut prng = Prng::with_seed(2023);
+        for dist in Distribution::ALL {
+            for num_items in [1, 2, 63, 64, 65, 1_000, 20_000] {
+                let vec = dist.generate(&mut prng, num_items, 5_000);
+                let mut expected = vec.clone();
+                expected.sort();
+                let mut sorted = vec;
+                tim_sort(&mut sorted);
+                assert_eq!(sorted, expected, "{dist} with {num_items} items");
+            }
+        }
+    }
+
+    #[test]
+    fn test_tim_sort_is_stable() {
+        let keys = make_random_vec(10_000, 50);
+        let vec: Vec<(i32, usize)> = keys.into_iter().zip(0..).collect();
+        let mut expected = vec.clone();
+        // The standard library's sort is stable.
+        expected.sort_by_key(|&(k, _)| k);
+        let mut sorted = vec;
+        tim_sort_by_key(&mut sorted, |&(k, _)| k);
+        assert_eq!(sorted, expected);
+    }
+
+    #[test]
+    fn test_stable_across_descending_runs() {
+        // Equal keys inside a descending stretch must not be reversed.
+        let vec: Vec<(i32, usize)> = (0..500).map(|i| (9 - (i / 50) as i32, i)).collect();
+        let mut expected = vec.clone();
+        expected.sort_by_key(|&(k, _)| k);
+        let mut sorted = vec;
+        tim_sort_by_key(&mut sorted, |&(k, _)| k);
+        assert_eq!(sorted, expected);
+    }
+
+    #[test]
+    fn test_galloping_merges() {
+        // Interleaved blocks of very different lengths make the merges gallop
+        // in both directions.
+        let mut vec = Vec::new();
+        for block in 0..40 {
+            let width = if block % 2 == 0 { 1 } else { 300 };
+            vec.extend((0..width).map(|x| block * 1_000 + x));
+        }
+        let mut expected = vec.clone();
+        expected.sort();
+        let mut prng = Prng::with_seed(7);
+        prng.shuffle(&mut vec);
+        let half = vec.len() / 2;
+        vec[..half].sort();
+        vec[half..].sort();
+        tim_sort(&mut vec);
+        assert_eq!(vec, expected);
+    }
+
+    #[test]
+    fn test_tim_sort_strings() {
+        let mut vec: Vec<String> = ["pear", "apple", "fig", "apple", "kiwi"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        tim_sort(&mut vec);
+        assert_eq!(vec, ["apple", "apple", "fig", "kiwi", "pear"]);
+        tim_sort_by(&mut vec, |a, b| b.cmp(a));
+        assert_eq!(vec, ["pear", "kiwi", "fig", "apple", "apple"]);
+    }
+
+    #[test]
+    fn test_min_run_length() {
+        assert_eq!(min_run_length(63), 63);
+        assert_eq!(min_run_length(64), 32);
+        assert_eq!(min_run_length(65), 33);
+        for len in [1_000, 12_345, 1 << 20] {
+            let run = min_run_length(len);
+            assert!((MIN_MERGE / 2..=MIN_MERGE).contains(&run), "{len}");
+        }
+    }
+
+    #[test]
+    fn test_gallop() {
+        let vec = [1, 2, 2, 3, 5, 8, 13, 21, 34];
+        for key in 0..40 {
+            let expected = vec.iter().filter(|&&x| x <= key).count();
+            assert_eq!(gallop(&vec, |&x| x <= key), expected, "{key}");
+        }
+        assert_eq!(gallop(&[] as &[i32], |_| true), 0);
+    }
+}
@@ -0,0 +1,30 @@
+//! Totally ordered wrappers for types that only implement `PartialOrd`.
+
+use std::cmp::Ordering;
+
+/// An `f64` ordered by `f64::total_cmp`, so it can be used with `Ord` sorts.
+///
+/// Negative zero sorts before positive zero and NaNs sort to the ends
+/// according to their sign.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TotalF64(pub f64);
+
+impl PartialEq for TotalF64 {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for TotalF64 {}
+
+impl PartialOrd for TotalF64 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TotalF64 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
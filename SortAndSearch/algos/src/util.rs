@@ -0,0 +1,357 @@
+//! Helpers shared by the sorting and searching front-ends.
+
+use std::fmt::{self, Display};
+use std::io;
+use std::io::{BufRead, Write};
+use std::ops::{Bound, RangeBounds};
+
+use crate::distribution::Distribution;
+use crate::prng::{Prng, RandomSource};
+
+/// Make a vector of `num_items` random values in the range [0, max).
+///
+/// The values come from a fresh [`Prng::new`], so `PRNG_SEED` makes them
+/// reproducible.
+pub fn make_random_vec(num_items: i32, max: i32) -> Vec<i32> {
+    make_random_vec_with(&mut Prng::new(), num_items, max)
+}
+
+/// Make a vector of `num_items` random values in the range [0, max), drawn
+/// from any random source.
+pub fn make_random_vec_with<R>(prng: &mut R, num_items: i32, max: i32) -> Vec<i32>
+where
+    R: RandomSource + ?Sized,
+{
+    let mut vec: Vec<i32> = Vec::with_capacity(num_items as usize);
+    for _ in 0..num_items {
+        vec.push(prng.next_i32(0, max));
+    }
+    vec
+}
+
+/// Print at most `num_items` items.
+pub fn print_vec<T: Display>(vec: &[T], num_items: usize) {
+    let max = vec.len().min(num_items);
+
+    let mut string = String::new();
+    string.push('[');
+
+    vec[0..max]
+        .iter()
+        .for_each(|x| string.push_str(&format!(" {}", x)));
+    string.push(']');
+    println!("{string}");
+}
+
+// ...
+/// Prompt the user for an i32 in `range` on stdin until they enter a valid
+/// one.
+///
+/// Returns `Ok(None)` if stdin reaches end of file.
+pub fn get_i32(prompt: &str, range: impl RangeBounds<i32>) -> Result<Option<i32>, InputError> {
+    read_i32(&mut io::stdin().lock(), &mut io::stdout(), prompt, range)
+}
+
+/// Write `prompt` to `output` and read an i32 in `range` from `input`.
+///
+/// Invalid or out-of-range answers are reported on `output` and the prompt
+/// is repeated. Returns `Ok(None)` at end of file, so Ctrl-D quits cleanly.
+pub fn read_i32<R, W>(
+    input: &mut R,
+    output: &mut W,
+    prompt: &str,
+    range: impl RangeBounds<i32>,
+) -> Result<Option<i32>, InputError>
+where
+    R: BufRead + ?Sized,
+    W: Write + ?Sized,
+{
+    loop {
+        let Some(trimmed) = read_string(input, output, prompt)? else {
+            return Ok(None);
+        };
+        match trimmed.parse::<i32>() {
+            Ok(value) if range.contains(&value) => return Ok(Some(value)),
+            Ok(value) => writeln!(
+                output,
+                "{value} is out of range; enter {}",
+                describe_range(&range)
+            )?,
+            Err(_) => writeln!(output, "'{trimmed}' is not an integer")?,
+        }
+    }
+}
+
+// Describe the range in words, such as "a number from 1 to 10".
+fn describe_range(range: &impl RangeBounds<i32>) -> String {
+    let min = match range.start_bound() {
+        Bound::Included(&min) => Some(min),
+        Bound::Excluded(&min) => min.checked_add(1),
+        Bound::Unbounded => None,
+    };
+    let max = match range.end_bound() {
+        Bound::Included(&max) => Some(max),
+        Bound::Excluded(&max) => max.checked_sub(1),
+        Bound::Unbounded => None,
+    };
+    match (min, max) {
+        (Some(min), Some(max)) => format!("a number from {min} to {max}"),
+        (Some(min), None) => format!("a number of at least {min}"),
+        (None, Some(max)) => format!("a number of at most {max}"),
+        (None, None) => "a number".to_string(),
+    }
+}
+
+/// The error returned when reading interactive input fails.
+#[derive(Debug)]
+pub enum InputError {
+    /// Reading the answer or writing the prompt failed.
+    Io(io::Error),
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InputError::Io(err) => write!(f, "error reading input: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for InputError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            InputError::Io(err) => Some(err),
+        }
+    }
+}
+
+impl From<io::Error> for InputError {
+    fn from(err: io::Error) -> Self {
+        InputError::Io(err)
+    }
+}
+
+/// Prompt the user for a line of text on stdin and return it trimmed.
+///
+/// Returns `Ok(None)` if stdin reaches end of file.
+pub fn get_string(prompt: &str) -> Result<Option<String>, InputError> {
+    read_string(&mut io::stdin().lock(), &mut io::stdout(), prompt)
+}
+
+/// Write `prompt` to `output` and read a line from `input`, trimmed.
+///
+/// Returns `Ok(None)` at end of file.
+pub fn read_string<R, W>(
+    input: &mut R,
+    output: &mut W,
+    prompt: &str,
+) -> Result<Option<String>, InputError>
+where
+    R: BufRead + ?Sized,
+    W: Write + ?Sized,
+{
+    write!(output, "{prompt}")?;
+    output.flush()?;
+
+    let mut str_value = String::new();
+    if input.read_line(&mut str_value)? == 0 {
+        // End the prompt's line so the shell starts on a fresh one.
+        writeln!(output)?;
+        return Ok(None);
+    }
+    Ok(Some(str_value.trim().to_string()))
+}
+
+/// Prompt the user for a data distribution on stdin until they enter a
+/// valid one. A blank answer means [`Distribution::Uniform`].
+///
+/// Returns `Ok(None)` if stdin reaches end of file.
+pub fn get_distribution(prompt: &str) -> Result<Option<Distribution>, InputError> {
+    read_distribution(&mut io::stdin().lock(), &mut io::stdout(), prompt)
+}
+
+/// Write `prompt` to `output` and read a data distribution from `input`.
+///
+/// A blank answer means [`Distribution::Uniform`]. Unknown names are
+/// reported on `output` and the prompt is repeated. Returns `Ok(None)` at end
+/// of file.
+pub fn read_distribution<R, W>(
+    input: &mut R,
+    output: &mut W,
+    prompt: &str,
+) -> Result<Option<Distribution>, InputError>
+where
+    R: BufRead + ?Sized,
+    W: Write + ?Sized,
+{
+    loop {
+        let Some(answer) = read_string(input, output, prompt)? else {
+            return Ok(None);
+        };
+        if answer.is_empty() {
+            return Ok(Some(Distribution::default()));
+        }
+        match answer.parse() {
+            Ok(dist) => return Ok(Some(dist)),
+            Err(err) => writeln!(output, "{err}")?,
+        }
+    }
+}
+
+/// Return true if every element is less than or equal to its successor.
+pub fn is_sorted<T: PartialOrd>(vec: &[T]) -> bool {
+    vec.windows(2).all(|w| w[0] <= w[1])
+}
+
+/// Print whether the vector is sorted.
+pub fn check_sorted<T: PartialOrd>(vec: &[T]) {
+    if is_sorted(vec) {
+        println!("The vector is sorted!");
+    } else {
+        println!("The vector is NOT sorted!");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_make_random_vec() {
+        let num_items = 10;
+        let max = 100;
+        let vec = make_random_vec(num_items, max);
+        assert_eq!(vec.len(), num_items as usize);
+        for &x in vec.iter() {
+            assert!(x >= 0 && x <= max);
+        }
+    }
+
+    #[test]
+    fn test_make_random_vec_with_seed() {
+        let a = make_random_vec_with(&mut Prng::with_seed(7), 100, 1_000);
+        let b = make_random_vec_with(&mut Prng::with_seed(7), 100, 1_000);
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_make_random_vec_with_any_source() {
+        use crate::prng::{Lcg, Pcg32, SplitMix64, XorShift128Plus};
+
+        let sources: [Box<dyn RandomSource>; 4] = [
+            Box::new(Lcg::with_seed(1)),
+            Box::new(Pcg32::with_seed(1)),
+            Box::new(SplitMix64::with_seed(1)),
+            Box::new(XorShift128Plus::with_seed(1)),
+        ];
+        for mut source in sources {
+            let vec = make_random_vec_with(source.as_mut(), 1_000, 10);
+            assert!(vec.iter().all(|&x| (0..10).contains(&x)));
+        }
+    }
+
+    // Run read_i32 on the given input and return its result and output.
+    fn read(input: &str, range: impl RangeBounds<i32>) -> (Option<i32>, String) {
+        let mut output = Vec::new();
+        let value = read_i32(&mut input.as_bytes(), &mut output, "> ", range).unwrap();
+        (value, String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn test_read_i32() {
+        assert_eq!(read("42\n", ..), (Some(42), "> ".to_string()));
+        assert_eq!(read("  -7  \n", ..).0, Some(-7));
+        // The last line does not need a newline.
+        assert_eq!(read("5", 1..).0, Some(5));
+    }
+
+    #[test]
+    fn test_read_i32_reprompts() {
+        let (value, output) = read("abc\n\n0\n3\n", 1..=10);
+        assert_eq!(value, Some(3));
+        assert_eq!(
+            output,
+            "> 'abc' is not an integer\n\
+             > '' is not an integer\n\
+             > 0 is out of range; enter a number from 1 to 10\n\
+             > "
+        );
+    }
+
+    #[test]
+    fn test_read_i32_eof() {
+        assert_eq!(read("", ..), (None, "> \n".to_string()));
+        assert_eq!(read("x\n", ..).0, None);
+        assert_eq!(read("99999999999\n", ..).0, None);
+    }
+
+    #[test]
+    fn test_read_string() {
+        let mut output = Vec::new();
+        let answer = read_string(&mut "  hello \n".as_bytes(), &mut output, "> ").unwrap();
+        assert_eq!(answer.as_deref(), Some("hello"));
+        let answer = read_string(&mut "".as_bytes(), &mut output, "> ").unwrap();
+        assert_eq!(answer, None);
+        assert_eq!(output, b"> > \n");
+    }
+
+    #[test]
+    fn test_read_distribution() {
+        let read = |input: &str| {
+            let mut output = Vec::new();
+            let dist = read_distribution(&mut input.as_bytes(), &mut output, "> ").unwrap();
+            (dist, String::from_utf8(output).unwrap())
+        };
+        assert_eq!(read("sorted\n").0, Some(Distribution::Sorted));
+        assert_eq!(read("\n").0, Some(Distribution::Uniform));
+        assert_eq!(read("").0, None);
+
+        let (dist, output) = read("spiral\nreverse\n");
+        assert_eq!(dist, Some(Distribution::Reverse));
+        assert!(output.starts_with("> unknown distribution 'spiral'"));
+        assert!(output.ends_with("\n> "));
+        assert_eq!(read("spiral\n").0, None);
+    }
+
+    #[test]
+    fn test_describe_range() {
+        assert_eq!(describe_range(&(1..)), "a number of at least 1");
+        assert_eq!(describe_range(&(..10)), "a number of at most 9");
+        assert_eq!(describe_range(&(-1..=1)), "a number from -1 to 1");
+        assert_eq!(describe_range(&..), "a number");
+    }
+
+    #[test]
+    fn test_read_i32_io_error() {
+        struct Broken;
+
+        impl Write for Broken {
+            fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+                Err(io::Error::other("closed"))
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let result = read_i32(&mut "1\n".as_bytes(), &mut Broken, "> ", ..);
+        assert!(matches!(result, Err(InputError::Io(_))));
+    }
+
+    #[test]
+    fn test_check_sorted() {
+        let vec = vec![1, 2, 3];
+        check_sorted(&vec);
+        assert!(is_sorted(&vec));
+        let vec = vec![3, 2, 1];
+        check_sorted(&vec);
+        assert!(!is_sorted(&vec));
+    }
+
+    #[test]
+    fn test_is_sorted_empty() {
+        let vec: Vec<i32> = Vec::new();
+        assert!(is_sorted(&vec));
+    }
+}
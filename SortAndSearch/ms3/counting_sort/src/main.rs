@@ -1,200 +1,106 @@
-mod prng;
+use std::process;
+
+use algos::cli::{Args, Cli, Flag};
+use algos::records::{create_output, open_input, read_customers, write_customers};
+use algos::sort::counting::make_customers;
+use algos::sort::{counting_sort_with_stats, Customer};
+use algos::util::{check_sorted, print_vec};
+
+const CLI: Cli = Cli {
+    name: "counting_sort",
+    about: "Sort customers by their number of purchases with counting sort. \
+            Customers are random unless --input names an id,num_purchases CSV file; \
+            then --max, if given, is the largest number of purchases allowed.",
+    flags: &[
+        Flag::Items,
+        Flag::Max,
+        Flag::Seed,
+        Flag::Print,
+        Flag::Dist,
+        Flag::Input,
+        Flag::Output,
+    ],
+};
 
-use core::fmt;
-use std::io::{self, Write};
-
-use prng::Prng;
-
-#[derive(Debug, Default, Clone)]
-struct Customer {
-    id: String,
-    num_purchases: i32,
-}
-
-impl PartialEq for Customer {
-    fn eq(&self, other: &Self) -> bool {
-        self.num_purchases == other.num_purchases
-    }
-}
-
-impl PartialOrd for Customer {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.num_purchases.partial_cmp(&other.num_purchases)
-    }
-}
-
-impl fmt::Display for Customer {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "({}, {})", self.id, self.num_purchases)
-    }
-}
-
-fn make_random_vec(num_items: i32, max: i32) -> Vec<Customer> {
-    // Prepare a Prng.
-    let mut prng = Prng::new();
-
-    let mut vec: Vec<Customer> = Vec::with_capacity(num_items as usize);
+fn main() {
+    let args = CLI.parse_env();
+    let (vec, max) = match &args.input {
+        Some(path) => {
+            let vec = load_customers(path);
+            // Trust --max when given, so out-of-range rows are reported.
+            let max = args
+                .max
+                .unwrap_or_else(|| vec.iter().map(|c| c.num_purchases).max().unwrap_or(0));
+            (vec, max)
+        }
+        None => random_customers(&args),
+    };
+
+    let (sorted_vec, stats) = match counting_sort_with_stats(&vec, max) {
+        Ok(sorted) => sorted,
+        Err(err) => {
+            eprintln!("Error sorting customers: {err}");
+            process::exit(1);
+        }
+    };
+
+    // Write CSV when reading CSV or when asked to; otherwise show a preview.
+    if args.input.is_some() || args.output.is_some() {
+        eprintln!("Counting sort made {stats}");
+        let path = args.output.as_deref();
+        let result = create_output(path).and_then(|out| write_customers(out, &sorted_vec));
+        if let Err(err) = result {
+            eprintln!("Error writing '{}': {err}", path.unwrap_or("-"));
+            process::exit(1);
+        }
+    } else {
+        check_sorted(&sorted_vec);
+        println!("Counting sort made {stats}");
 
-    for i in 0..num_items {
-        vec.push(Customer {
-            id: format!("C{}", i),
-            num_purchases: prng.next_i32(0, max),
-        });
+        print_vec(&sorted_vec, args.print.unwrap_or(10));
     }
-    vec
 }
 
-// Print at most num_items items.
-fn print_vec(vec: &[Customer], num_items: i32) {
-    let mut max = vec.len();
-    if max > num_items as usize {
-        max = num_items as usize;
+// Read customers from the CSV file, reporting the rows that were skipped.
+fn load_customers(path: &str) -> Vec<Customer> {
+    let import = match open_input(Some(path)).and_then(read_customers) {
+        Ok(import) => import,
+        Err(err) => {
+            eprintln!("Error reading '{path}': {err}");
+            process::exit(1);
+        }
+    };
+    for err in &import.errors {
+        eprintln!("{path}: skipped {err}");
     }
-    let mut string = String::new();
-    string.push('[');
-
-    vec[0..max]
-        .iter()
-        .for_each(|x| string.push_str(&format!(" {}", x)));
-    string.push(']');
-    println!("{string}");
-}
-
-// ...
-// Prompt the user for an i32.
-fn get_i32(prompt: &str) -> i32 {
-    print!("{prompt}");
-    io::stdout().flush().unwrap();
-
-    let mut str_value = String::new();
-    io::stdin()
-        .read_line(&mut str_value)
-        .expect("Error reading input");
-
-    let trimmed = str_value.trim();
-    trimmed.parse::<i32>().expect("Error parsing integer")
+    import.customers
 }
 
-fn counting_sort(v: &[Customer], max: i32) -> Vec<Customer> {
-    let mut counts = vec![0; (max + 1) as usize];
-    let mut sorted = vec![Customer::default(); v.len()];
-
-    // Count the number of occurrences of each value.
-    v.iter().for_each(|x| counts[x.num_purchases as usize] += 1);
-
-    // Modify counts to contain the number of elements <= i.
-    for i in 1..counts.len() {
-        counts[i] += counts[i - 1];
-    }
-
-    // Build the sorted array.
-    for i in (0..v.len()).rev() {
-        let index = counts[v[i].num_purchases as usize] - 1;
-        sorted[index] = v[i].clone();
-        counts[v[i].num_purchases as usize] -= 1;
-    }
-    sorted
-}
-
-fn check_sorted(vec: &[Customer]) {
-    let mut sorted = true;
-    let mut i = 0;
-    while sorted && i < vec.len() - 1 {
-        sorted = vec[i] <= vec[i + 1];
-        i += 1;
-    }
-    if sorted {
-        println!("The vector is sorted!");
+// Make customers with random purchases, prompting for anything missing.
+// Return them with the bound on their purchases.
+fn random_customers(args: &Args) -> (Vec<Customer>, i32) {
+    let num_items = CLI.require(
+        args.items,
+        Flag::Items,
+        "Please specify number of items to be sorted: ",
+    );
+    let max = CLI.require(
+        args.max,
+        Flag::Max,
+        "Please specify the maximum value for an item: ",
+    );
+    let dist = CLI.dist_or_prompt(
+        args,
+        "Please specify the distribution (blank for uniform): ",
+    );
+    let mut prng = args.prng();
+    let seed = format!("Seed: {} (set PRNG_SEED to replay)", prng.initial_seed());
+    // Keep the seed out of CSV written to stdout.
+    if args.output.is_some() {
+        eprintln!("{seed}");
     } else {
-        println!("The vector is NOT sorted!");
-    }
-}
-
-fn main() {
-    let num_items = get_i32("Please specify number of items to be sorted: ");
-    let max = get_i32("Please specify the maximum value for an item: ");
-    let vec = make_random_vec(num_items, max);
-
-    let sorted_vec = counting_sort(&vec, max);
-
-    check_sorted(&sorted_vec);
-
-    print_vec(&sorted_vec, 10);
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_counting_sort() {
-        let v = vec![
-            Customer {
-                id: "C1".to_string(),
-                num_purchases: 5,
-            },
-            Customer {
-                id: "C2".to_string(),
-                num_purchases: 3,
-            },
-            Customer {
-                id: "C3".to_string(),
-                num_purchases: 1,
-            },
-            Customer {
-                id: "C4".to_string(),
-                num_purchases: 8,
-            },
-            Customer {
-                id: "C5".to_string(),
-                num_purchases: 2,
-            },
-        ];
-        let expected = vec![
-            Customer {
-                id: "C3".to_string(),
-                num_purchases: 1,
-            },
-            Customer {
-                id: "C5".to_string(),
-                num_purchases: 2,
-            },
-            Customer {
-                id: "C2".to_string(),
-                num_purchases: 3,
-            },
-            Customer {
-                id: "C1".to_string(),
-                num_purchases: 5,
-            },
-            Customer {
-                id: "C4".to_string(),
-                num_purchases: 8,
-            },
-        ];
-        let result = counting_sort(&v, 8);
-        assert_eq!(result, expected);
-    }
-
-    #[test]
-    fn test_same_elements() {
-        let max = 100;
-        let v = make_random_vec(10_000, max);
-        let mut result = counting_sort(&v, max);
-        let (identical, res) = v
-            .iter()
-            .fold((true, &mut result), |(is_identical, res), elem| {
-                if is_identical {
-                    if let Some(pos) = res.iter().position(|e| e == elem) {
-                        res.remove(pos);
-                        (is_identical, res)
-                    } else {
-                        (false, res)
-                    }
-                } else {
-                    (is_identical, res)
-                }
-            });
-        assert!(identical && res.is_empty());
+        println!("{seed}");
     }
+    let customers = make_customers(&dist.generate(&mut prng, num_items, max));
+    (customers, max)
 }
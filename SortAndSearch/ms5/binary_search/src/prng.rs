@@ -1,46 +0,0 @@
-use std::time::{SystemTime, UNIX_EPOCH};
-
-// ...
-
-// ************
-// *** Prng ***
-// ************
-pub struct Prng {
-    seed: u32,
-}
-
-impl Prng {
-    pub fn new() -> Self {
-        let mut prng = Self { seed: 0 };
-        prng.randomize();
-        prng
-    }
-
-    fn randomize(&mut self) {
-        let millis = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .expect("Time went backwards")
-            .as_millis();
-        self.seed = millis as u32;
-    }
-
-    // Return a pseudorandom value in the range [0, 2147483647].
-    pub fn next_u32(&mut self) -> u32 {
-        self.seed = self.seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
-        self.seed %= 1 << 31;
-        self.seed
-    }
-
-    // Return a pseudorandom value in the range [0.0, 1.0).
-    pub fn next_f64(&mut self) -> f64 {
-        let f = self.next_u32() as f64;
-        f / (2147483647.0 + 1.0)
-    }
-
-    // Return a pseudorandom value in the range [min, max).
-    pub fn next_i32(&mut self, min: i32, max: i32) -> i32 {
-        let range = (max - min) as f64;
-        let result = min as f64 + range * self.next_f64();
-        result as i32
-    }
-}
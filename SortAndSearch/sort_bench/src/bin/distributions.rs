@@ -0,0 +1,31 @@
+//! Compare the general-purpose sorts on every data distribution.
+
+use algos::sort::Radix;
+use algos::{heap_sort, lsd_radix_sort, quick_sort, tim_sort, Distribution};
+use sort_bench::{num_items_arg, seeded_prng, time_sort};
+
+fn main() {
+    let num_items = num_items_arg(1_000_000);
+    let mut prng = seeded_prng();
+    println!("Sorting {num_items} items in [0, {num_items})");
+    println!(
+        "{:<16} {:>10} {:>10} {:>10} {:>10}",
+        "", "quick", "heap", "radix", "tim"
+    );
+
+    for dist in Distribution::ALL {
+        let vec = dist.generate(&mut prng, num_items as i32, num_items as i32);
+        let quick = time_sort(&vec, quick_sort);
+        let heap = time_sort(&vec, heap_sort);
+        let radix = time_sort(&vec, |v| lsd_radix_sort(v, Radix::Bits8));
+        let tim = time_sort(&vec, tim_sort);
+        println!(
+            "{:<16} {:>8.3} s {:>8.3} s {:>8.3} s {:>8.3} s",
+            dist.to_string(),
+            quick.as_secs_f64(),
+            heap.as_secs_f64(),
+            radix.as_secs_f64(),
+            tim.as_secs_f64()
+        );
+    }
+}
@@ -0,0 +1,23 @@
+//! Sweep the quicksort insertion sort threshold on the same random data.
+
+use algos::sort::quick::QuickSort;
+use algos::util::make_random_vec_with;
+use sort_bench::{num_items_arg, seeded_prng, time_sort};
+
+fn main() {
+    let num_items = num_items_arg(10_000_000);
+    let mut prng = seeded_prng();
+    let vec = make_random_vec_with(&mut prng, num_items as i32, num_items as i32);
+    println!("Sorting {num_items} items");
+
+    for insertion_threshold in [0, 4, 8, 12, 16, 24, 32, 48, 64] {
+        let elapsed = time_sort(&vec, |v| {
+            QuickSort {
+                insertion_threshold,
+                ..Default::default()
+            }
+            .sort(v)
+        });
+        println!("{insertion_threshold:>3} {:>10.3} s", elapsed.as_secs_f64());
+    }
+}
@@ -0,0 +1,37 @@
+//! Compare the merge sort variants with quicksort on the same random data.
+
+use algos::quick_sort;
+use algos::sort::merge::{MergeSort, MergeVariant};
+use algos::util::make_random_vec_with;
+use sort_bench::{num_items_arg, seeded_prng, time_sort};
+
+fn main() {
+    let num_items = num_items_arg(1_000_000);
+    let mut prng = seeded_prng();
+    let vec = make_random_vec_with(&mut prng, num_items as i32, num_items as i32);
+    let mut sorted = vec.clone();
+    sorted.sort();
+    println!("Sorting {num_items} integers");
+
+    for (name, input) in [("random", &vec), ("sorted", &sorted)] {
+        println!("{name} input:");
+        let elapsed = time_sort(input, quick_sort);
+        println!("  {:<16} {:>10.3} s", "quick_sort", elapsed.as_secs_f64());
+        for variant in [
+            MergeVariant::TopDown,
+            MergeVariant::BottomUp,
+            MergeVariant::Natural,
+        ] {
+            let sorter = MergeSort {
+                variant,
+                ..Default::default()
+            };
+            let elapsed = time_sort(input, |v| sorter.sort(v));
+            println!(
+                "  {:<16} {:>10.3} s",
+                format!("{variant:?}"),
+                elapsed.as_secs_f64()
+            );
+        }
+    }
+}
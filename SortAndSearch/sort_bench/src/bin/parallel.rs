@@ -0,0 +1,17 @@
+//! Compare the sequential and parallel quicksort on the same random data.
+
+use algos::util::make_random_vec_with;
+use algos::{par_quick_sort, quick_sort};
+use sort_bench::{num_items_arg, seeded_prng, time_sort};
+
+fn main() {
+    let num_items = num_items_arg(10_000_000);
+    let mut prng = seeded_prng();
+    let vec = make_random_vec_with(&mut prng, num_items as i32, num_items as i32);
+    println!("Sorting {num_items} items");
+
+    let sequential = time_sort(&vec, quick_sort);
+    println!("{:<10} {:>10.3} s", "sequential", sequential.as_secs_f64());
+    let parallel = time_sort(&vec, par_quick_sort);
+    println!("{:<10} {:>10.3} s", "parallel", parallel.as_secs_f64());
+}
@@ -0,0 +1,36 @@
+//! Compare the quicksort partitioning schemes on the same random data.
+
+use algos::sort::quick::{PartitionScheme, QuickSort};
+use algos::util::make_random_vec_with;
+use sort_bench::{num_items_arg, seeded_prng, time_sort};
+
+fn main() {
+    let num_items = num_items_arg(10_000_000);
+    let mut prng = seeded_prng();
+
+    // Mostly distinct keys, then a duplicate-heavy input.
+    for max in [num_items, 10] {
+        let vec = make_random_vec_with(&mut prng, num_items as i32, max as i32);
+        println!("Sorting {num_items} items in [0, {max})");
+
+        for scheme in [
+            PartitionScheme::Fold,
+            PartitionScheme::Lomuto,
+            PartitionScheme::Hoare,
+            PartitionScheme::ThreeWay,
+        ] {
+            let elapsed = time_sort(&vec, |v| {
+                QuickSort {
+                    scheme,
+                    ..Default::default()
+                }
+                .sort(v)
+            });
+            println!(
+                "{:<8} {:>10.3} s",
+                format!("{scheme:?}"),
+                elapsed.as_secs_f64()
+            );
+        }
+    }
+}
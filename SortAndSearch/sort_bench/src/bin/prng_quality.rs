@@ -0,0 +1,24 @@
+//! Print the statistical test results for every random source.
+
+use algos::prng::quality::run_all;
+use algos::prng::{Lcg, Pcg32, RandomSource, SplitMix64, XorShift128Plus};
+use sort_bench::seeded_prng;
+
+fn main() {
+    let seed = seeded_prng().initial_seed();
+    let sources: [(&str, Box<dyn RandomSource>); 4] = [
+        ("Lcg", Box::new(Lcg::with_seed(seed))),
+        ("Pcg32", Box::new(Pcg32::with_seed(seed))),
+        ("SplitMix64", Box::new(SplitMix64::with_seed(seed))),
+        (
+            "XorShift128Plus",
+            Box::new(XorShift128Plus::with_seed(seed)),
+        ),
+    ];
+    for (name, mut source) in sources {
+        println!("{name}");
+        for result in run_all(source.as_mut()) {
+            println!("  {result}");
+        }
+    }
+}
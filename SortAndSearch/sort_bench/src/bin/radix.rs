@@ -0,0 +1,38 @@
+//! Compare radix sort with quicksort on the same random data.
+
+use algos::quick_sort;
+use algos::sort::radix::{lsd_radix_sort, msd_radix_sort, Radix};
+use algos::util::make_random_vec_with;
+use sort_bench::{num_items_arg, seeded_prng, time_sort};
+
+fn main() {
+    let num_items = num_items_arg(10_000_000);
+    let mut prng = seeded_prng();
+    let vec = make_random_vec_with(&mut prng, num_items as i32, num_items as i32);
+    println!("Sorting {num_items} integers");
+
+    let elapsed = time_sort(&vec, quick_sort);
+    println!("{:<16} {:>10.3} s", "quick_sort", elapsed.as_secs_f64());
+    for radix in [Radix::Bits8, Radix::Bits16] {
+        let elapsed = time_sort(&vec, |v| lsd_radix_sort(v, radix));
+        println!(
+            "{:<16} {:>10.3} s",
+            format!("lsd {radix:?}"),
+            elapsed.as_secs_f64()
+        );
+    }
+
+    let strings: Vec<String> = vec.iter().map(|x| x.to_string()).collect();
+    println!("Sorting {num_items} strings");
+
+    let elapsed = time_sort(&strings, quick_sort);
+    println!("{:<16} {:>10.3} s", "quick_sort", elapsed.as_secs_f64());
+    for radix in [Radix::Bits8, Radix::Bits16] {
+        let elapsed = time_sort(&strings, |v| msd_radix_sort(v, radix));
+        println!(
+            "{:<16} {:>10.3} s",
+            format!("msd {radix:?}"),
+            elapsed.as_secs_f64()
+        );
+    }
+}
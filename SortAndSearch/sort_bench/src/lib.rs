@@ -0,0 +1,43 @@
+//! Helpers shared by the benchmark binaries.
+//!
+//! Run a benchmark with `cargo run --release -p sort_bench --bin <name>`.
+
+use std::env;
+use std::process;
+use std::time::{Duration, Instant};
+
+use algos::cli::USAGE_ERROR;
+use algos::prng::{self, Prng};
+
+/// Read the number of items from the first command-line argument.
+pub fn num_items_arg(default: usize) -> usize {
+    match env::args().nth(1) {
+        Some(arg) => arg.parse().expect("Error parsing number of items"),
+        None => default,
+    }
+}
+
+/// Create a generator and print its seed, so the data can be replayed with
+/// `PRNG_SEED`. Exit with [`USAGE_ERROR`] if `PRNG_SEED` is not a valid seed.
+pub fn seeded_prng() -> Prng {
+    if let Err(err) = prng::env_seed() {
+        eprintln!("{err}");
+        process::exit(USAGE_ERROR);
+    }
+    let prng = Prng::new();
+    println!("Seed: {}", prng.initial_seed());
+    prng
+}
+
+/// Run `sort` on a copy of `vec` and return how long it took.
+pub fn time_sort<T: Clone + PartialOrd>(vec: &[T], sort: impl FnOnce(&mut [T])) -> Duration {
+    let mut copy = vec.to_vec();
+    let start = Instant::now();
+    sort(&mut copy);
+    let elapsed = start.elapsed();
+    assert!(
+        algos::util::is_sorted(&copy),
+        "sort produced unsorted output"
+    );
+    elapsed
+}
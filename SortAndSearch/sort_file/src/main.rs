@@ -0,0 +1,100 @@
+//! Sort integers or CSV rows from a file or stdin, like `sort -n`.
+
+use std::error::Error;
+use std::io;
+use std::process;
+
+use algos::cli::{ArgError, Args, Cli, Flag};
+use algos::records::{create_output, open_input, read_numbers, write_numbers, CsvTable};
+use algos::sort::ExternalSort;
+
+const CLI: Cli = Cli {
+    name: "sort_file",
+    about: "Sort newline-separated integers, or CSV rows by a numeric column. \
+            With --memory or --format, sort 32-bit integers externally, \
+            spilling sorted runs to temporary files.",
+    flags: &[
+        Flag::Input,
+        Flag::Output,
+        Flag::Column,
+        Flag::Algo,
+        Flag::Memory,
+        Flag::Format,
+    ],
+};
+
+fn main() {
+    let args = CLI.parse_env();
+    let external = args.memory.is_some() || args.format.is_some();
+    if external {
+        // External sorting always uses quicksort on plain integers.
+        let mode = if args.memory.is_some() {
+            Flag::Memory
+        } else {
+            Flag::Format
+        };
+        if args.column.is_some() {
+            CLI.fail(ArgError::Conflict(Flag::Column, mode));
+        }
+        if args.algo.is_some() {
+            CLI.fail(ArgError::Conflict(Flag::Algo, mode));
+        }
+        if args.input.is_some() && args.input == args.output && args.input.as_deref() != Some("-") {
+            eprintln!("{}: an external sort cannot write over its input", CLI.name);
+            process::exit(1);
+        }
+    }
+
+    let result = if external {
+        sort_external(&args)
+    } else {
+        sort_in_memory(&args)
+    };
+    if let Err(err) = result {
+        eprintln!("{}: {err}", CLI.name);
+        process::exit(1);
+    }
+}
+
+fn sort_in_memory(args: &Args) -> Result<(), Box<dyn Error>> {
+    let (input, output) = (args.input.as_deref(), args.output.as_deref());
+    let algo = args.algo.unwrap_or_default();
+    let reader = open_input(input).map_err(|err| cannot("open", input, err))?;
+    // Only create the output once the input has been read, so it may be the
+    // same file.
+    match args.column {
+        Some(column) => {
+            let mut table = CsvTable::read(reader, column)?;
+            table.sort(algo)?;
+            let writer = create_output(output).map_err(|err| cannot("create", output, err))?;
+            table.write(writer)?;
+        }
+        None => {
+            let mut numbers = read_numbers(reader)?;
+            algo.sort_by_key(&mut numbers, |&n| n)?;
+            let writer = create_output(output).map_err(|err| cannot("create", output, err))?;
+            write_numbers(writer, &numbers)?;
+        }
+    }
+    Ok(())
+}
+
+fn sort_external(args: &Args) -> Result<(), Box<dyn Error>> {
+    let (input, output) = (args.input.as_deref(), args.output.as_deref());
+    let defaults = ExternalSort::default();
+    let sorter = ExternalSort {
+        memory_budget: args.memory.unwrap_or(defaults.memory_budget),
+        format: args.format.unwrap_or(defaults.format),
+        ..defaults
+    };
+    let reader = open_input(input).map_err(|err| cannot("open", input, err))?;
+    let writer = create_output(output).map_err(|err| cannot("create", output, err))?;
+    let stats = sorter.sort(reader, writer)?;
+    eprintln!("Sorted {} items using {} runs", stats.items, stats.runs);
+    Ok(())
+}
+
+// Describe a failure to open or create the file at path.
+fn cannot(action: &str, path: Option<&str>, err: io::Error) -> String {
+    format!("cannot {action} '{}': {err}", path.unwrap_or("-"))
+}